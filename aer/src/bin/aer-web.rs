@@ -8,13 +8,16 @@ use std::path::PathBuf;
 use aer::{log_data, logging, ChecksumType};
 use aer_upd::data::Url;
 use aer_upd::web::errors::WebError;
-use aer_upd::web::{LinkElement, LinkType, ResponseType, WebRequest, WebResponse};
+use aer_upd::web::{
+    filter_by_extension, LinkElement, LinkType, ResponseType, WebRequest, WebResponse,
+};
 #[cfg(feature = "human")]
 use human_bytes::human_bytes;
 #[cfg(feature = "human")]
 use human_panic::setup_panic;
 use lazy_static::lazy_static;
 use log::{error, info, warn};
+use regex::Regex;
 use structopt::StructOpt;
 use yansi::{Color, Paint, Style};
 
@@ -37,6 +40,11 @@ struct ParseArguments {
     /// The regular expression to use when parsing the specified `url`.
     #[structopt(long, short)]
     regex: Option<String>,
+
+    /// Only output the links that ends with one of the specified extensions
+    /// (*ie: `.exe`, `.msi` or `.zip`*). Can be specified multiple times.
+    #[structopt(long = "extension", number_of_values = 1)]
+    extensions: Vec<String>,
 }
 
 #[derive(StructOpt)]
@@ -82,6 +90,11 @@ struct DownloadArguments {
     /// must exist. [default: %TEMP%]
     #[structopt(long, parse(from_os_str))]
     work_dir: Option<PathBuf>,
+
+    /// Only output the information about the file that would be downloaded,
+    /// without writing anything to the work directory.
+    #[structopt(long)]
+    dry_run: bool,
 }
 
 #[derive(StructOpt)]
@@ -136,8 +149,16 @@ fn main() {
 }
 
 fn parse_cmd(request: WebRequest, args: ParseArguments) {
+    let extensions: Vec<&str> = args.extensions.iter().map(|ext| ext.as_str()).collect();
+
     match parse_website(request, args.url, args.regex) {
         Ok((parent, links)) => {
+            let links = if extensions.is_empty() {
+                links
+            } else {
+                filter_by_extension(links, &extensions)
+            };
+
             info!(
                 "Successfully parsed '{}'",
                 Color::Magenta.paint(parent.link)
@@ -200,6 +221,15 @@ fn parse_website(
     url: Url,
     regex: Option<String>,
 ) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+    if let Some(ref regex) = regex {
+        // Validate the regex early, to avoid a request to the server when it
+        // would fail anyway
+        Regex::new(regex).map_err(|err| WebError::InvalidRegex {
+            pattern: regex.clone(),
+            error: err.to_string(),
+        })?;
+    }
+
     let response = request.get_html_response(url.as_str())?;
 
     if let Some(ref regex) = regex {
@@ -241,9 +271,29 @@ fn download_file(request: WebRequest, args: DownloadArguments) -> Result<(), Web
                 }
             }
 
-            response.set_work_dir(&args.work_dir.unwrap());
+            response.set_work_dir(args.work_dir.as_ref().unwrap());
 
             let (etag, last_modified) = get_info(&response);
+
+            if args.dry_run {
+                let file_name = args.file_name.or_else(|| response.file_name());
+                let path = args.work_dir.unwrap().join(file_name.unwrap_or_default());
+                info!(
+                    "Dry run enabled, the file would be downloaded to '{}'",
+                    Color::Magenta.paint(path.display())
+                );
+                info!("The following information was given by the server:");
+                print_string("ETag", etag.trim_matches('"'));
+                print_string("Last Modified", &last_modified);
+                if let Some(len) = response.content_length() {
+                    print_line("Content Length", len);
+                } else {
+                    print_line("Content Length", "None");
+                }
+
+                return Ok(());
+            }
+
             let result = if let Some(file_name) = args.file_name {
                 let file_name_str = Some(file_name.as_str());
                 response.read(file_name_str)?
@@ -325,16 +375,8 @@ fn validate_local_file(args: &DownloadArguments, file_name: &str) -> Result<bool
 }
 
 fn get_info<T: WebResponse>(response: &T) -> (String, String) {
-    let headers = response.get_headers();
-    let mut etag = String::new();
-    let mut last_modified = String::new();
-
-    if let Some(etag_val) = headers.get("etag") {
-        etag = etag_val.to_string();
-    }
-    if let Some(modified_val) = headers.get("last-modified") {
-        last_modified = modified_val.to_string();
-    }
+    let etag = response.get_header("etag").unwrap_or_default();
+    let last_modified = response.get_header("last-modified").unwrap_or_default();
 
     (etag, last_modified)
 }
@@ -0,0 +1,226 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Allows downloading multiple files at the same time, typically used when
+//! both a 32bit and a 64bit binary needs to be downloaded.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use aer_upd::web::errors::WebError;
+use aer_upd::web::{ResponseType, WebRequest, WebResponse};
+use log::warn;
+
+use crate::ChecksumType;
+
+/// Downloads all of the specified urls to the work directory, using at most
+/// `max_concurrency` downloads at the same time.
+///
+/// ## Arguments
+///
+/// - `urls`: The urls to download, keyed by the architecture (or any other
+///   identifier) the url is for (*ie: `arch32` and `arch64`*).
+/// - `work_dir`: The directory the files will be downloaded to.
+/// - `checksum_type`: The type of the checksum to generate for every downloaded
+///   file.
+/// - `max_concurrency`: The maximum amount of files to download at the same
+///   time.
+///
+/// ## Returns
+///
+/// A map using the same keys as `urls`, with either the path and checksum of
+/// the downloaded file or the error that occurred when downloading the file.
+pub fn download_concurrently(
+    urls: &HashMap<String, String>,
+    work_dir: &Path,
+    checksum_type: ChecksumType,
+    max_concurrency: usize,
+) -> HashMap<String, Result<(PathBuf, String), WebError>> {
+    let queue: Vec<(String, String)> = urls
+        .iter()
+        .map(|(key, url)| (key.clone(), url.clone()))
+        .collect();
+    let workers = max_concurrency.max(1).min(queue.len());
+    let queue = Arc::new(Mutex::new(queue));
+    let (sender, receiver) = mpsc::channel();
+    let mut handles = Vec::with_capacity(workers);
+
+    for _ in 0..workers {
+        let queue = Arc::clone(&queue);
+        let sender = sender.clone();
+        let work_dir = work_dir.to_path_buf();
+
+        handles.push(thread::spawn(move || {
+            let request = WebRequest::create();
+
+            loop {
+                let next = queue.lock().unwrap().pop();
+                let (key, url) = match next {
+                    Some(item) => item,
+                    None => break,
+                };
+
+                let result = download_file(&request, &key, &url, &work_dir, checksum_type);
+
+                if sender.send((key, result)).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
+    drop(sender);
+    let mut results: HashMap<_, _> = receiver.iter().collect();
+
+    for handle in handles {
+        if handle.join().is_err() {
+            warn!("A download worker stopped unexpectedly");
+        }
+    }
+
+    for (key, url) in urls {
+        results.entry(key.clone()).or_insert_with(|| {
+            Err(WebError::Other(format!(
+                "The download of '{}' did not complete, the download worker stopped unexpectedly",
+                url
+            )))
+        });
+    }
+
+    results
+}
+
+/// Downloads a single file, the name of the file is prefixed with the key of
+/// the url to prevent urls with the same file name (*ie: `x86/setup.exe` and
+/// `x64/setup.exe`*) from being written to the same path.
+fn download_file(
+    request: &WebRequest,
+    key: &str,
+    url: &str,
+    work_dir: &Path,
+    checksum_type: ChecksumType,
+) -> Result<(PathBuf, String), WebError> {
+    let mut response = match request.get_binary_response(url, None, None)? {
+        ResponseType::New(response, _) => response,
+        ResponseType::Updated(status) => {
+            return Err(WebError::Other(format!(
+                "The server responded with status {} without any content for '{}'",
+                status, url
+            )));
+        }
+    };
+    response.set_work_dir(work_dir);
+    let file_name = response
+        .file_name()
+        .ok_or_else(|| WebError::Other(format!("Unable to extract file name of '{}'", url)))?;
+
+    let path = response.read(Some(&format!("{}-{}", key, file_name)))?;
+    let checksum = checksum_type.generate(&path)?;
+
+    Ok((path, checksum))
+}
+
+#[cfg(test)]
+mod tests {
+    use aer_web::test_server;
+
+    use super::*;
+
+    /// Starts a local server, that responds with the path of the request as
+    /// the content (or 404 for paths ending with `/missing.bin`).
+    fn serve() -> String {
+        test_server::serve(|request| {
+            if request.path.ends_with("/missing.bin") {
+                test_server::response(404, &[], b"")
+            } else {
+                test_server::response(200, &[], format!("Content of {}", request.path).as_bytes())
+            }
+        })
+    }
+
+    #[test]
+    fn download_concurrently_should_download_all_files() {
+        let url = serve();
+        let work_dir = std::env::temp_dir().join("aer-concurrent-downloads");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let mut urls = HashMap::new();
+        urls.insert("arch32".to_owned(), format!("{}/test-x86.bin", url));
+        urls.insert("arch64".to_owned(), format!("{}/test-x64.bin", url));
+
+        let actual = download_concurrently(&urls, &work_dir, ChecksumType::Sha256, 2);
+
+        assert_eq!(actual.len(), 2);
+        for (key, name) in &[("arch32", "test-x86.bin"), ("arch64", "test-x64.bin")] {
+            let (path, checksum) = actual[*key].as_ref().unwrap();
+            assert_eq!(path, &work_dir.join(format!("{}-{}", key, name)));
+            assert_eq!(
+                std::fs::read_to_string(path).unwrap(),
+                format!("Content of /{}", name)
+            );
+            assert_eq!(checksum, &ChecksumType::Sha256.generate(path).unwrap());
+        }
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn download_concurrently_should_download_urls_with_same_file_name_separately() {
+        let url = serve();
+        let work_dir = std::env::temp_dir().join("aer-concurrent-downloads-same-name");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let mut urls = HashMap::new();
+        urls.insert("arch32".to_owned(), format!("{}/x86/setup.exe", url));
+        urls.insert("arch64".to_owned(), format!("{}/x64/setup.exe", url));
+
+        let actual = download_concurrently(&urls, &work_dir, ChecksumType::Sha256, 2);
+
+        let (path32, checksum32) = actual["arch32"].as_ref().unwrap();
+        let (path64, checksum64) = actual["arch64"].as_ref().unwrap();
+        assert_ne!(path32, path64);
+        assert_ne!(checksum32, checksum64);
+        assert_eq!(
+            std::fs::read_to_string(path32).unwrap(),
+            "Content of /x86/setup.exe"
+        );
+        assert_eq!(
+            std::fs::read_to_string(path64).unwrap(),
+            "Content of /x64/setup.exe"
+        );
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn download_concurrently_should_return_errors_for_each_failing_url() {
+        let url = serve();
+        let work_dir = std::env::temp_dir().join("aer-concurrent-downloads-errors");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let mut urls = HashMap::new();
+        urls.insert("arch32".to_owned(), format!("{}/test-x86.bin", url));
+        urls.insert("arch64".to_owned(), format!("{}/missing.bin", url));
+
+        let actual = download_concurrently(&urls, &work_dir, ChecksumType::Sha256, 1);
+
+        assert!(actual["arch32"].is_ok());
+        assert!(matches!(
+            actual["arch64"],
+            Err(WebError::HttpStatus { code: 404, .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn download_concurrently_should_return_empty_map_without_urls() {
+        let actual = download_concurrently(
+            &HashMap::new(),
+            &std::env::temp_dir(),
+            ChecksumType::Sha256,
+            4,
+        );
+
+        assert!(actual.is_empty());
+    }
+}
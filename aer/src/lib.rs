@@ -1,6 +1,7 @@
 // Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
 // Licensed under the MIT license. See LICENSE.txt file in the project
 
+pub mod downloads;
 pub mod logging;
 
 use std::fmt::Display;
@@ -16,7 +17,7 @@ use sha2::digest::generic_array::ArrayLength;
 use sha2::{Digest, Sha256, Sha512};
 use structopt::StructOpt;
 
-#[derive(Debug, PartialEq, StructOpt)]
+#[derive(Debug, Clone, Copy, PartialEq, StructOpt)]
 pub enum ChecksumType {
     Md5,
     Sha1,
@@ -67,27 +67,29 @@ fn run_update(package_file: &Path) -> Result<(), Box<dyn std::error::Error>> {
 
     if data.updater().has_chocolatey() {
         let choco = data.updater().chocolatey();
-        let (_, urls) = match &choco.parse_url {
-            Some(chocolatey::ChocolateyParseUrl::Url(url)) => {
-                request.get_html_response(url.as_str())?.read(None)?
-            }
-            Some(chocolatey::ChocolateyParseUrl::UrlWithRegex { url, ref regex }) => {
-                info!("Parsing links on '{}' using regex '{}'", url, regex);
-                let (parent, urls) = request.get_html_response(url.as_str())?.read(Some(regex))?;
-                if !urls.is_empty() {
-                    info!("{} links found, using first one to get links!", urls.len());
-                    let url = urls.get(0).unwrap();
-                    info!("Parsing links on '{}'", url.link);
-                    request.get_html_response(url.link.as_str())?.read(None)?
-                } else {
-                    (parent, urls)
-                }
-            }
-            _ => {
+        let parse_url = match choco.parse_url {
+            Some(ref parse_url) => parse_url,
+            None => {
                 warn!("No url have been specified to parse!");
                 std::process::exit(5);
             }
         };
+        let url = parse_url.url();
+
+        let (_, urls) = if let Some(regex) = parse_url.regex() {
+            info!("Parsing links on '{}' using regex '{}'", url, regex);
+            let (parent, urls) = request.get_html_response(url.as_str())?.read(Some(regex))?;
+            if !urls.is_empty() {
+                info!("{} links found, using first one to get links!", urls.len());
+                let url = urls.get(0).unwrap();
+                info!("Parsing links on '{}'", url.link);
+                request.get_html_response(url.link.as_str())?.read(None)?
+            } else {
+                (parent, urls)
+            }
+        } else {
+            request.get_html_response(url.as_str())?.read(None)?
+        };
 
         let mut aarch32 = None;
         let mut aarch64 = None;
@@ -78,6 +78,28 @@ fn should_parse_with_regex_command() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn should_fail_parsing_with_invalid_regex() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aer-web")?;
+    let log_path = LOG_DIR.join("aer-web-tests-parse-invalid-regex.log");
+
+    cmd.args(&[
+        "parse",
+        "https://not-really.important",
+        "--regex",
+        "(?P<version>[\\d",
+        "--log",
+        log_path.to_str().unwrap(),
+    ])
+    .env("NO_COLOR", "true");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "The regular expression '(?P<version>[\\d' is not valid",
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn should_download_file_and_output_message() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("aer-web")?;
@@ -268,3 +290,77 @@ fn should_no_download_file_when_checksum_matches() -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+#[test]
+fn should_not_create_file_on_dry_run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aer-web")?;
+    let log_path = LOG_DIR.join("aer-web-tests-dry-run.log");
+    let work_dir = std::env::temp_dir();
+    let full_path = work_dir.join("codecov-linux-x64.zip");
+    if full_path.exists() {
+        std::fs::remove_file(&full_path)?;
+    }
+
+    cmd.args(&[
+        "download",
+        "https://github.com/codecov/codecov-exe/releases/download/1.11.0/codecov-linux-x64.zip",
+        "--dry-run",
+        "--keep-files",
+        "--log",
+        log_path.to_str().unwrap(),
+        "--work-dir",
+        work_dir.to_str().unwrap(),
+    ])
+    .env("NO_COLOR", "true");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains(format!(
+            "Dry run enabled, the file would be downloaded to '{}'",
+            full_path.display()
+        ))
+        .and(predicate::str::contains(
+            "ETag : a9da76dd5aa96fcee6de685cc1996075",
+        ))
+        .and(predicate::str::contains("Content Length : 16376743"))
+        .and(predicate::str::contains("Downloading").not()),
+    );
+
+    assert!(predicate::path::missing().eval(&full_path));
+
+    Ok(())
+}
+
+#[test]
+fn should_download_file_from_local_file_url() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("aer-web")?;
+    let log_path = LOG_DIR.join("aer-web-tests-file-url.log");
+    let work_dir = std::env::temp_dir().join("aer-web-tests-file-url");
+    std::fs::create_dir_all(&work_dir)?;
+    let fixture = std::env::current_dir()?
+        .join("test-data")
+        .join("checksum-test.bin.txt");
+    let url = aer_upd::data::Url::from_file_path(&fixture).unwrap();
+
+    cmd.args(&[
+        "download",
+        url.as_str(),
+        "--log",
+        log_path.to_str().unwrap(),
+        "--work-dir",
+        work_dir.to_str().unwrap(),
+    ])
+    .env("NO_COLOR", "true");
+
+    cmd.assert().success().stdout(
+        predicate::str::contains("Copying '")
+            .and(predicate::str::contains("Successfully copied"))
+            .and(predicate::str::contains(
+                "Checksum : 856ee247a62ef795346a4e5f9d1106373a2add6185aa2b2609e6816496c7c839",
+            ))
+            .and(predicate::str::contains("The web server responded").not()),
+    );
+
+    let _ = std::fs::remove_dir_all(work_dir);
+
+    Ok(())
+}
@@ -14,6 +14,11 @@ pub fn empty_version() -> Versions {
     Versions::SemVer(SemVersion::new(0, 0, 0))
 }
 
+#[cfg(feature = "chocolatey")]
+pub fn valid_exit_codes() -> Vec<i32> {
+    vec![0]
+}
+
 pub fn maintainer() -> Vec<String> {
     vec![match std::env::var("AER_MAINTAINER") {
         Ok(maintainer) => maintainer,
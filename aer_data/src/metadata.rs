@@ -3,12 +3,18 @@
 
 #[cfg(feature = "chocolatey")]
 pub mod chocolatey;
+pub mod errors;
 
 use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::convert::Infallible;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use aer_license::LicenseType;
+use aer_version::Versions;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -18,6 +24,7 @@ use url::Url;
 pub enum Description {
     None,
     Location {
+        #[cfg_attr(feature = "serialize", serde(with = "portable_path"))]
         from: PathBuf,
         skip_start: u16,
         skip_end: u16,
@@ -25,14 +32,241 @@ pub enum Description {
     Text(String),
 }
 
+/// Serializes paths using forward slashes as the separator, and accepts both
+/// forward and backward slashes when deserializing. This allows the same meta
+/// files to be shared between Windows and Unix systems.
+#[cfg(feature = "serialize")]
+mod portable_path {
+    use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+        let path = path.to_string_lossy().replace('\\', "/");
+
+        serializer.serialize_str(&path)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+        let path: String = String::deserialize(deserializer)?
+            .chars()
+            .map(|c| {
+                if c == '/' || c == '\\' {
+                    MAIN_SEPARATOR
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        Ok(PathBuf::from(path))
+    }
+}
+
+impl Description {
+    /// Returns `true` if no description have been set.
+    pub fn is_none(&self) -> bool {
+        *self == Description::None
+    }
+
+    /// Derives a one line summary from the markdown description, by using the
+    /// first sentence that is not part of a heading. Any links and emphasis are
+    /// stripped from the returned summary.
+    ///
+    /// Returns `None` if there is no description, if the description file could
+    /// not be read or if the description only contains headings.
+    pub fn summary(&self) -> Option<String> {
+        match self {
+            Description::None => None,
+            Description::Location {
+                from,
+                skip_start,
+                skip_end,
+            } => derive_summary(&read_location(from, *skip_start, *skip_end).ok()?),
+            Description::Text(text) => derive_summary(text),
+        }
+    }
+}
+
+/// Reads the description located in the specified file, skipping the
+/// specified amount of lines at the start and end of the file.
+fn read_location(from: &Path, skip_start: u16, skip_end: u16) -> std::io::Result<String> {
+    let file = std::fs::File::open(from)?;
+
+    read_skipping_lines(BufReader::new(file), skip_start, skip_end)
+}
+
+/// Reads all lines from the reader, except the first `skip_start` lines and
+/// the last `skip_end` lines. Only the lines that may need to be skipped at
+/// the end are kept in memory while reading, instead of the whole file.
+fn read_skipping_lines<R: BufRead>(
+    reader: R,
+    skip_start: u16,
+    skip_end: u16,
+) -> std::io::Result<String> {
+    read_skipping_lines_tracked(reader, skip_start, skip_end).map(|(text, _)| text)
+}
+
+/// Reads the lines the same way as [read_skipping_lines], but also returns the
+/// largest amount of lines that were kept in memory at the same time.
+fn read_skipping_lines_tracked<R: BufRead>(
+    reader: R,
+    skip_start: u16,
+    skip_end: u16,
+) -> std::io::Result<(String, usize)> {
+    let mut trailing = VecDeque::with_capacity(skip_end as usize + 1);
+    let mut max_trailing = 0;
+    let mut text = String::new();
+
+    for line in reader.lines().skip(skip_start as usize) {
+        trailing.push_back(line?);
+        max_trailing = max_trailing.max(trailing.len());
+
+        if trailing.len() > skip_end as usize {
+            if let Some(line) = trailing.pop_front() {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&line);
+            }
+        }
+    }
+
+    Ok((text, max_trailing))
+}
+
+/// Returns the first sentence of the first paragraph that is not a heading.
+fn derive_summary(markdown: &str) -> Option<String> {
+    let lines: Vec<&str> = markdown.lines().map(str::trim).collect();
+    let mut paragraph = Vec::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        let is_setext_title = lines
+            .get(index + 1)
+            .map_or(false, |next| !line.is_empty() && is_setext_underline(next));
+
+        if line.is_empty() || line.starts_with('#') || is_setext_underline(line) || is_setext_title
+        {
+            if !paragraph.is_empty() {
+                break;
+            }
+        } else {
+            paragraph.push(
+                line.trim_start_matches(&['-', '*', '+', '>'][..])
+                    .trim_start(),
+            );
+        }
+    }
+
+    let text = strip_markdown(&paragraph.join(" "));
+    let mut end = text.len();
+
+    for (index, ch) in text.char_indices() {
+        if matches!(ch, '.' | '!' | '?') {
+            let next = index + ch.len_utf8();
+            if text[next..]
+                .chars()
+                .next()
+                .map_or(true, char::is_whitespace)
+            {
+                end = next;
+                break;
+            }
+        }
+    }
+
+    let summary = text[..end].trim();
+
+    if summary.is_empty() {
+        None
+    } else {
+        Some(summary.to_owned())
+    }
+}
+
+fn is_setext_underline(line: &str) -> bool {
+    !line.is_empty() && (line.chars().all(|ch| ch == '=') || line.chars().all(|ch| ch == '-'))
+}
+
+/// Removes the most common inline markdown formatting from the text, links are
+/// replaced by their text, while images are removed.
+fn strip_markdown(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' | '`' => {}
+            '!' if chars.peek() == Some(&'[') => {
+                for ch in chars.by_ref() {
+                    if ch == ')' {
+                        break;
+                    }
+                }
+            }
+            '[' | ']' => {
+                if ch == ']' && chars.peek() == Some(&'(') {
+                    for ch in chars.by_ref() {
+                        if ch == ')' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '_' if result.is_empty()
+                || result.ends_with(char::is_whitespace)
+                || chars.peek().map_or(true, |next| !next.is_alphanumeric()) => {}
+            _ => result.push(ch),
+        }
+    }
+
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Default for Description {
+    fn default() -> Self {
+        Description::None
+    }
+}
+
 impl PartialEq<str> for Description {
     fn eq(&self, right: &str) -> bool {
         self == &Description::Text(right.into())
     }
 }
 
+impl FromStr for Description {
+    type Err = Infallible;
+
+    /// Creates a new text description, or [Description::None] if the specified
+    /// text is empty (or only contains whitespace).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Description::from(s))
+    }
+}
+
+impl From<&str> for Description {
+    fn from(text: &str) -> Self {
+        if text.trim().is_empty() {
+            Description::None
+        } else {
+            Description::Text(text.into())
+        }
+    }
+}
+
+impl From<String> for Description {
+    fn from(text: String) -> Self {
+        if text.trim().is_empty() {
+            Description::None
+        } else {
+            Description::Text(text)
+        }
+    }
+}
+
 /// Stores common values that are related to 1 or more package managers.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 #[non_exhaustive]
 pub struct PackageMetadata {
@@ -90,6 +324,10 @@ pub struct PackageMetadata {
 
     #[cfg(feature = "chocolatey")]
     #[cfg_attr(docsrs, doc(cfg(feature = "chocolatey")))]
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     chocolatey: Option<chocolatey::ChocolateyMetadata>,
 }
 
@@ -113,6 +351,29 @@ impl PackageMetadata {
         &self.id
     }
 
+    /// Creates a copy of the current metadata with a different identifier, and
+    /// optionally a different version. All other values are shared with the
+    /// current metadata.
+    ///
+    /// The version is set on the chocolatey metadata, which will be created if
+    /// it is not already set. When the `chocolatey` feature is not enabled, the
+    /// version is ignored.
+    pub fn with_overrides(&self, id: &str, version: Option<Versions>) -> PackageMetadata {
+        let mut metadata = self.clone();
+        metadata.id = id.into();
+
+        #[cfg(feature = "chocolatey")]
+        if let Some(version) = version {
+            let mut choco = metadata.chocolatey().into_owned();
+            choco.version = version;
+            metadata.set_chocolatey(choco);
+        }
+        #[cfg(not(feature = "chocolatey"))]
+        let _ = version;
+
+        metadata
+    }
+
     /// Returns wether metadata regarding chocolatey is already set or not.
     #[cfg(feature = "chocolatey")]
     #[cfg_attr(docsrs, doc(cfg(feature = "chocolatey")))]
@@ -168,9 +429,12 @@ impl PackageMetadata {
         self.maintainers = maintainers;
     }
 
-    pub fn set_project_url(&mut self, url: &str) {
-        let url = Url::parse(url).unwrap(); // We want a failure here to abort the program
-        self.project_url = url;
+    /// Sets the main endpoint (homepage) of the software, returning an error
+    /// and keeping the existing url if the specified url is not valid.
+    pub fn set_project_url(&mut self, url: &str) -> Result<(), url::ParseError> {
+        self.project_url = Url::parse(url)?;
+
+        Ok(())
     }
 
     pub fn set_license(&mut self, license: LicenseType) {
@@ -186,8 +450,126 @@ impl Default for PackageMetadata {
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
+    #[rstest]
+    #[case(
+        "# My Package\n\nThis is **my** [package](https://example.org/a.b). It does things.",
+        "This is my package."
+    )]
+    #[case(
+        "My Package\n==========\n\n![logo](logo.png) A _simple_ tool for snake_case names! Other",
+        "A simple tool for snake_case names!"
+    )]
+    #[case(
+        "## Features\n\n- Supports `version` 1.2.3. Nothing else",
+        "Supports version 1.2.3."
+    )]
+    #[case(
+        "No sentence end\nsecond line\n\nOther paragraph.",
+        "No sentence end second line"
+    )]
+    fn summary_should_derive_first_sentence_from_markdown(
+        #[case] text: &str,
+        #[case] expected: &str,
+    ) {
+        let description = Description::Text(text.into());
+
+        assert_eq!(description.summary(), Some(expected.to_owned()));
+    }
+
+    #[rstest]
+    #[case(Description::None)]
+    #[case(Description::Text("# Heading\n\n## Other heading\n".into()))]
+    fn summary_should_return_none_without_sentences(#[case] description: Description) {
+        assert_eq!(description.summary(), None);
+    }
+
+    #[rstest]
+    #[case(0, 0, "1\n2\n3\n4\n5")]
+    #[case(2, 0, "3\n4\n5")]
+    #[case(0, 2, "1\n2\n3")]
+    #[case(1, 1, "2\n3\n4")]
+    #[case(3, 3, "")]
+    fn read_skipping_lines_should_skip_start_and_end_lines(
+        #[case] skip_start: u16,
+        #[case] skip_end: u16,
+        #[case] expected: &str,
+    ) {
+        let reader = std::io::Cursor::new("1\n2\n3\n4\n5\n");
+
+        let actual = read_skipping_lines(reader, skip_start, skip_end).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(0, 0)]
+    #[case(0, 2)]
+    #[case(1, 1)]
+    #[case(3, 3)]
+    #[case(0, 10)]
+    fn read_skipping_lines_should_only_keep_skipped_end_lines_in_memory(
+        #[case] skip_start: u16,
+        #[case] skip_end: u16,
+    ) {
+        let reader = std::io::Cursor::new("1\n2\n3\n4\n5\n");
+
+        let (_, max_trailing) = read_skipping_lines_tracked(reader, skip_start, skip_end).unwrap();
+
+        assert!(max_trailing <= skip_end as usize + 1);
+    }
+
+    #[test]
+    fn read_location_should_read_large_files() {
+        let path = std::env::temp_dir().join("aer-data-large-description.md");
+        {
+            use std::io::Write;
+            let mut file = std::io::BufWriter::new(std::fs::File::create(&path).unwrap());
+            for i in 0..100_000 {
+                writeln!(file, "Line {}", i).unwrap();
+            }
+        }
+
+        let actual = read_location(&path, 10, 500);
+        let tracked = std::fs::File::open(&path)
+            .and_then(|file| read_skipping_lines_tracked(std::io::BufReader::new(file), 10, 500));
+        let _ = std::fs::remove_file(&path);
+
+        let actual = actual.unwrap();
+        let lines: Vec<&str> = actual.lines().collect();
+        assert_eq!(lines.len(), 100_000 - 10 - 500);
+        assert_eq!(lines.first(), Some(&"Line 10"));
+        assert_eq!(lines.last(), Some(&"Line 99499"));
+
+        // Only the lines that may be skipped at the end are kept in memory
+        let (text, max_trailing) = tracked.unwrap();
+        assert_eq!(text, actual);
+        assert!(max_trailing <= 500 + 1);
+    }
+
+    #[test]
+    fn summary_should_derive_sentence_from_location() {
+        let path = std::env::temp_dir().join("aer-data-summary-location.md");
+        std::fs::write(
+            &path,
+            "<!-- header -->\n# Test\n\nThe actual [summary](https://example.org). Test\n",
+        )
+        .unwrap();
+        let description = Description::Location {
+            from: path.clone(),
+            skip_start: 1,
+            skip_end: 0,
+        };
+
+        let actual = description.summary();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(actual, Some("The actual summary.".to_owned()));
+    }
+
     #[test]
     fn new_should_create_default_metadata_with_expected_values() {
         let expected = PackageMetadata {
@@ -214,6 +596,42 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn with_overrides_should_keep_shared_values_and_change_overridden_values() {
+        let mut base = PackageMetadata::new("base-package");
+        base.summary = "Shared summary".into();
+        base.set_maintainers(&["AdmiringWorm"]);
+        base.set_license(LicenseType::Expression("MIT".into()));
+        base.set_project_url("https://example.org").unwrap();
+
+        let actual = base.with_overrides("other-package", None);
+
+        assert_eq!(actual.id(), "other-package");
+        assert_eq!(actual.summary, base.summary);
+        assert_eq!(actual.maintainers(), base.maintainers());
+        assert_eq!(actual.license(), base.license());
+        assert_eq!(actual.project_url(), base.project_url());
+        assert_eq!(base.id(), "base-package");
+    }
+
+    #[test]
+    #[cfg(feature = "chocolatey")]
+    fn with_overrides_should_set_version_on_chocolatey_metadata() {
+        let mut base = PackageMetadata::new("base-package");
+        let mut choco = chocolatey::ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+        choco.version = Versions::parse("1.0.0").unwrap();
+        base.set_chocolatey(choco);
+
+        let actual = base.with_overrides("other-package", Some(Versions::parse("2.0.0").unwrap()));
+
+        assert_eq!(
+            actual.chocolatey().version,
+            Versions::parse("2.0.0").unwrap()
+        );
+        assert_eq!(actual.chocolatey().authors(), ["AdmiringWorm"]);
+        assert_eq!(base.chocolatey().version, Versions::parse("1.0.0").unwrap());
+    }
+
     #[test]
     fn id_should_return_set_identifier() {
         const EXPECTED: &str = "my-awesome-test-package";
@@ -257,6 +675,30 @@ mod tests {
         assert_eq!(data.chocolatey(), Cow::Owned(expected));
     }
 
+    #[test]
+    fn set_project_url_should_set_valid_url() {
+        let mut pkg = PackageMetadata::new("test");
+
+        pkg.set_project_url("https://github.com/WormieCorp/aer")
+            .unwrap();
+
+        assert_eq!(
+            pkg.project_url(),
+            &Url::parse("https://github.com/WormieCorp/aer").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_project_url_should_return_error_on_invalid_url() {
+        let mut pkg = PackageMetadata::new("test");
+        let expected = pkg.project_url().clone();
+
+        let result = pkg.set_project_url("not a valid url");
+
+        assert_eq!(result, Err(url::ParseError::RelativeUrlWithoutBase));
+        assert_eq!(pkg.project_url(), &expected);
+    }
+
     #[cfg(feature = "chocolatey")]
     #[test]
     fn chocolatey_should_return_default_data() {
@@ -268,4 +710,68 @@ mod tests {
             Cow::Owned(chocolatey::ChocolateyMetadata::new())
         );
     }
+
+    #[test]
+    fn description_should_parse_text_from_str() {
+        let actual: Description = "Some description".parse().unwrap();
+
+        assert_eq!(actual, Description::Text("Some description".into()));
+    }
+
+    #[test]
+    fn description_should_be_created_from_str_and_string() {
+        let from_str: Description = "Some description".into();
+        let from_string: Description = String::from("Some description").into();
+
+        assert_eq!(from_str, "Some description");
+        assert_eq!(from_string, "Some description");
+    }
+
+    #[rstest(text, case(""), case("  "), case("\n"))]
+    fn description_should_be_none_for_empty_text(text: &str) {
+        let parsed: Description = text.parse().unwrap();
+        let from_str = Description::from(text);
+        let from_string = Description::from(text.to_owned());
+
+        assert_eq!(parsed, Description::None);
+        assert_eq!(from_str, Description::None);
+        assert_eq!(from_string, Description::None);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn description_location_should_serialize_path_with_forward_slashes() {
+        let description = Description::Location {
+            from: PathBuf::from("docs\\description.md"),
+            skip_start: 1,
+            skip_end: 0,
+        };
+
+        let actual = serde_json::to_string(&description).unwrap();
+
+        assert_eq!(
+            actual,
+            r#"{"from":"docs/description.md","skip_start":1,"skip_end":0}"#
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn description_location_should_deserialize_paths_with_any_separator() {
+        let expected = Description::Location {
+            from: PathBuf::from("docs").join("description.md"),
+            skip_start: 1,
+            skip_end: 0,
+        };
+
+        let unix: Description =
+            serde_json::from_str(r#"{"from":"docs/description.md","skip_start":1,"skip_end":0}"#)
+                .unwrap();
+        let windows: Description =
+            serde_json::from_str(r#"{"from":"docs\\description.md","skip_start":1,"skip_end":0}"#)
+                .unwrap();
+
+        assert_eq!(unix, expected);
+        assert_eq!(windows, expected);
+    }
 }
@@ -10,12 +10,12 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 
-use aer_version::Versions;
+use aer_version::{SemanticVersionError, Versions};
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::prelude::Description;
+use crate::prelude::{Description, MetadataError};
 
 /// Basic structure to hold information regarding a
 /// package that are only specific to creating Chocolatey
@@ -55,9 +55,17 @@ pub struct ChocolateyMetadata {
     lowercase_id: bool,
 
     /// The title of the software.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub title: Option<String>,
 
     /// The copyright of the software
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub copyright: Option<String>,
 
     /// The version of the Chocolatey package, can be automatically updated and
@@ -70,9 +78,17 @@ pub struct ChocolateyMetadata {
 
     /// The authors/developers of the software that the package will be created
     /// for.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     authors: Vec<String>,
 
     /// The description of the software.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Description::is_none")
+    )]
     pub description: Description,
 
     /// Wether the license of the software requires users to accept the license.
@@ -83,18 +99,35 @@ pub struct ChocolateyMetadata {
     pub require_license_acceptance: bool,
 
     /// The url to the documentation of the software.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub documentation_url: Option<Url>,
 
     /// The url to where bugs or features to the software should be reported.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub issues_url: Option<Url>,
 
-    #[cfg_attr(feature = "serialize", serde(default))]
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
     tags: Vec<String>,
 
-    #[cfg_attr(feature = "serialize", serde(default))]
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     release_notes: Option<String>,
 
-    #[cfg_attr(feature = "serialize", serde(default))]
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default, skip_serializing_if = "HashMap::is_empty")
+    )]
     dependencies: HashMap<String, Versions>,
 }
 
@@ -143,6 +176,34 @@ impl ChocolateyMetadata {
         self.set_description(Description::Text(description.into()));
     }
 
+    /// Appends the specified text to the end of the current description,
+    /// separated by a new line. If no description is set, the text will be
+    /// used as the new description.
+    ///
+    /// A description located in a file is read and converted to a text
+    /// description before the text is appended, an error is returned if the
+    /// file could not be read (the description is then left unchanged).
+    pub fn append_description<S: AsRef<str>>(&mut self, text: S) -> std::io::Result<()> {
+        let text = text.as_ref();
+        let mut description = match &self.description {
+            Description::None => String::new(),
+            Description::Location {
+                from,
+                skip_start,
+                skip_end,
+            } => super::read_location(from, *skip_start, *skip_end)?,
+            Description::Text(description) => description.clone(),
+        };
+
+        if !description.is_empty() && !description.ends_with('\n') {
+            description.push('\n');
+        }
+        description.push_str(text);
+        self.description = Description::from(description);
+
+        Ok(())
+    }
+
     pub fn set_title(&mut self, title: &str) {
         if let Some(ref mut self_title) = self.title {
             self_title.clear();
@@ -192,14 +253,201 @@ impl ChocolateyMetadata {
         self
     }
 
+    /// Replaces the current authors with the comma or semicolon separated
+    /// authors in the specified string (*ie: the authors format used in a
+    /// `.nuspec` file*).
+    ///
+    /// Returns an error, and keeps the current authors, if the string do not
+    /// contain any authors.
+    pub fn set_authors_str<S: AsRef<str>>(&mut self, authors: S) -> Result<(), MetadataError> {
+        let authors: Vec<String> = authors
+            .as_ref()
+            .split(&[',', ';'][..])
+            .map(str::trim)
+            .filter(|author| !author.is_empty())
+            .map(Into::into)
+            .collect();
+
+        if authors.is_empty() {
+            return Err(MetadataError::EmptyAuthors);
+        }
+
+        self.authors = authors;
+
+        Ok(())
+    }
+
+    /// Parses the specified string and sets it as the version of the package.
+    ///
+    /// Returns an error, and keeps the current version, if the string is not a
+    /// valid version.
+    pub fn set_version<S: AsRef<str>>(&mut self, version: S) -> Result<(), MetadataError> {
+        let version = version.as_ref();
+        let parsed = Versions::parse(version).map_err(|err| MetadataError::InvalidVersion {
+            version: version.into(),
+            error: match err.downcast::<SemanticVersionError>() {
+                Ok(err) => *err,
+                Err(err) => SemanticVersionError::ParseError(err.to_string()),
+            },
+        })?;
+
+        self.set_version_obj(parsed);
+
+        Ok(())
+    }
+
+    /// Sets the version of the package.
+    pub fn set_version_obj(&mut self, version: Versions) {
+        self.version = version;
+    }
+
+    /// Adds a single tag to the current tags, unless the tag already exist.
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.into());
+        }
+    }
+
+    /// Adds multiple tags to the end of the current tags, ignoring any tag
+    /// that already exist.
+    pub fn add_tags<T: AsRef<str>>(&mut self, tags: &[T]) {
+        for tag in tags.iter() {
+            self.add_tag(tag.as_ref());
+        }
+    }
+
+    /// Removes the specified tag, returning `true` if the tag existed.
+    pub fn remove_tag<T: AsRef<str>>(&mut self, tag: T) -> bool {
+        let len = self.tags.len();
+        self.tags.retain(|t| t != tag.as_ref());
+
+        self.tags.len() != len
+    }
+
+    /// Converts all of the current tags to lowercase, which is the convention
+    /// used for tags on the Chocolatey community repository. Any tags that
+    /// are duplicated after being converted are removed, keeping the existing
+    /// order.
+    ///
+    /// Returns `true` if any of the tags were changed.
+    pub fn normalize_tags(&mut self) -> bool {
+        let tags = std::mem::take(&mut self.tags);
+        let mut changed = false;
+
+        for tag in tags {
+            let normalized = tag.to_lowercase();
+            changed |= normalized != tag;
+            if self.tags.contains(&normalized) {
+                changed = true;
+            } else {
+                self.tags.push(normalized);
+            }
+        }
+
+        changed
+    }
+
+    /// Replaces the current tags with the whitespace separated tags in the
+    /// specified string (*ie: the tags format used in a `.nuspec` file*).
+    pub fn set_tags_str<S: AsRef<str>>(&mut self, tags: S) {
+        self.tags.clear();
+
+        for tag in tags.as_ref().split_whitespace() {
+            self.add_tag(tag);
+        }
+    }
+
+    /// Merges the values of `other` into the current instance, this is
+    /// typically used when layering package specific values on top of a base
+    /// template.
+    ///
+    /// The following precedence is used when merging the values:
+    ///
+    /// - `title`, `copyright`, `release_notes`, `documentation_url` and
+    ///   `issues_url`: The value in `other` is used when it is set (not
+    ///   `None`).
+    /// - `version`: The value in `other` is used when it is not the empty
+    ///   `0.0.0` version.
+    /// - `authors`: The authors in `other` replaces the current authors when
+    ///   `other` is not empty.
+    /// - `description`: The value in `other` is used when it is not
+    ///   [Description::None].
+    /// - `lowercase_id` and `require_license_acceptance`: The value in `other`
+    ///   is only used when it has been changed from the default value of
+    ///   `true`.
+    /// - `tags`: The tags in `other` that do not already exist are appended to
+    ///   the current tags, keeping the existing order.
+    /// - `dependencies`: The dependencies are combined, if both instances
+    ///   contain the same dependency the version in `other` is used.
+    pub fn merge(&mut self, other: &ChocolateyMetadata) {
+        if !other.lowercase_id {
+            self.lowercase_id = false;
+        }
+        if other.title.is_some() {
+            self.title = other.title.clone();
+        }
+        if other.copyright.is_some() {
+            self.copyright = other.copyright.clone();
+        }
+        if other.version != crate::defaults::empty_version() {
+            self.version = other.version.clone();
+        }
+        if !other.authors.is_empty() {
+            self.authors = other.authors.clone();
+        }
+        if other.description != Description::None {
+            self.description = other.description.clone();
+        }
+        if !other.require_license_acceptance {
+            self.require_license_acceptance = false;
+        }
+        if other.documentation_url.is_some() {
+            self.documentation_url = other.documentation_url.clone();
+        }
+        if other.issues_url.is_some() {
+            self.issues_url = other.issues_url.clone();
+        }
+        if other.release_notes.is_some() {
+            self.release_notes = other.release_notes.clone();
+        }
+
+        for tag in other.tags.iter() {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+
+        for (id, version) in other.dependencies.iter() {
+            self.dependencies.insert(id.clone(), version.clone());
+        }
+    }
+
     /// Allows initializing and setting the Chocolatey metadata structure with
     /// the specified authors/developers of the software.
+    ///
+    /// ### Panics
+    ///
+    /// Panics if no authors are specified, use
+    /// [ChocolateyMetadata::try_with_authors] to get an error instead.
     pub fn with_authors<T>(values: &[T]) -> Self
+    where
+        T: Display,
+    {
+        match Self::try_with_authors(values) {
+            Ok(data) => data,
+            Err(_) => panic!("Invalid usage: Authors can not be empty!"),
+        }
+    }
+
+    /// Allows initializing and setting the Chocolatey metadata structure with
+    /// the specified authors/developers of the software, returning an error if
+    /// no authors are specified.
+    pub fn try_with_authors<T>(values: &[T]) -> Result<Self, MetadataError>
     where
         T: Display,
     {
         if values.is_empty() {
-            panic!("Invalid usage: Authors can not be empty!");
+            return Err(MetadataError::EmptyAuthors);
         }
 
         let mut data = Self::new();
@@ -212,7 +460,7 @@ impl ChocolateyMetadata {
 
         data.authors = new_authors;
 
-        data
+        Ok(data)
     }
 }
 
@@ -224,6 +472,8 @@ impl Default for ChocolateyMetadata {
 
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -292,6 +542,169 @@ mod tests {
         assert_eq!(actual.authors(), authors);
     }
 
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serialize_should_skip_empty_values() {
+        let data = ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+
+        let actual = serde_json::to_string(&data).unwrap();
+
+        assert_eq!(
+            actual,
+            r#"{"lowercase_id":true,"version":"0.0.0","authors":["AdmiringWorm"],"require_license_acceptance":true}"#
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn deserialize_should_use_defaults_for_skipped_values() {
+        let expected = ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+        let text = serde_json::to_string(&expected).unwrap();
+
+        let actual: ChocolateyMetadata = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(Description::None, "Features", "Features")]
+    #[case(Description::Text("Base text".into()), "Features", "Base text\nFeatures")]
+    #[case(Description::Text("Base text\n".into()), "Features", "Base text\nFeatures")]
+    fn append_description_should_append_text(
+        #[case] description: Description,
+        #[case] text: &str,
+        #[case] expected: &str,
+    ) {
+        let mut data = ChocolateyMetadata::new();
+        data.set_description(description);
+
+        data.append_description(text).unwrap();
+
+        assert_eq!(data.description(), &Description::Text(expected.into()));
+    }
+
+    #[test]
+    fn append_description_should_convert_location_to_text() {
+        let path = std::env::temp_dir().join("aer-data-append-description.md");
+        std::fs::write(&path, "# Header\nBase text\nFooter\n").unwrap();
+        let mut data = ChocolateyMetadata::new();
+        data.set_description(Description::Location {
+            from: path.clone(),
+            skip_start: 1,
+            skip_end: 1,
+        });
+
+        let result = data.append_description("Features");
+        let _ = std::fs::remove_file(&path);
+
+        result.unwrap();
+        assert_eq!(
+            data.description(),
+            &Description::Text("Base text\nFeatures".into())
+        );
+    }
+
+    #[test]
+    fn append_description_should_return_error_and_keep_missing_location() {
+        let description = Description::Location {
+            from: std::env::temp_dir().join("aer-data-append-missing.md"),
+            skip_start: 0,
+            skip_end: 0,
+        };
+        let mut data = ChocolateyMetadata::new();
+        data.set_description(description.clone());
+
+        let result = data.append_description("Features");
+
+        assert!(result.is_err());
+        assert_eq!(data.description(), &description);
+    }
+
+    #[test]
+    fn try_with_authors_should_set_specified_authors() {
+        let authors = ["AdmiringWorm", "Chocolatey"];
+
+        let actual = ChocolateyMetadata::try_with_authors(&authors).unwrap();
+
+        assert_eq!(actual.authors(), authors);
+    }
+
+    #[test]
+    fn try_with_authors_should_return_error_on_empty_authors() {
+        let val: Vec<String> = vec![];
+
+        let actual = ChocolateyMetadata::try_with_authors(&val);
+
+        assert_eq!(actual, Err(MetadataError::EmptyAuthors));
+    }
+
+    #[rstest]
+    #[case("AdmiringWorm, Chocolatey", &["AdmiringWorm", "Chocolatey"])]
+    #[case("AdmiringWorm;Chocolatey ; Kim", &["AdmiringWorm", "Chocolatey", "Kim"])]
+    #[case(" AdmiringWorm,; Chocolatey,", &["AdmiringWorm", "Chocolatey"])]
+    #[case("Kim J. Nordmo", &["Kim J. Nordmo"])]
+    fn set_authors_str_should_split_authors(#[case] authors: &str, #[case] expected: &[&str]) {
+        let mut data = ChocolateyMetadata::new();
+
+        data.set_authors_str(authors).unwrap();
+
+        assert_eq!(data.authors(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case(" , ;")]
+    fn set_authors_str_should_return_error_and_keep_authors_on_empty_authors(
+        #[case] authors: &str,
+    ) {
+        let mut data = ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+
+        let result = data.set_authors_str(authors);
+
+        assert_eq!(result, Err(MetadataError::EmptyAuthors));
+        assert_eq!(data.authors(), ["AdmiringWorm"]);
+    }
+
+    #[rstest]
+    #[case("1.2.3")]
+    #[case("2.1.0-beta.5")]
+    #[case("5.1.6.4")]
+    fn set_version_should_parse_and_set_version(#[case] version: &str) {
+        let mut data = ChocolateyMetadata::new();
+
+        data.set_version(version).unwrap();
+
+        assert_eq!(data.version, Versions::parse(version).unwrap());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("not-a-version")]
+    fn set_version_should_return_error_and_keep_version_on_invalid_version(#[case] version: &str) {
+        let mut data = ChocolateyMetadata::new();
+        data.set_version_obj(Versions::parse("1.0.0").unwrap());
+
+        let result = data.set_version(version);
+
+        assert!(matches!(
+            result,
+            Err(MetadataError::InvalidVersion {
+                version: ref v,
+                error: SemanticVersionError::ParseError(_),
+            }) if v == version
+        ));
+        assert_eq!(data.version, Versions::parse("1.0.0").unwrap());
+    }
+
+    #[test]
+    fn set_version_obj_should_set_version() {
+        let mut data = ChocolateyMetadata::new();
+
+        data.set_version_obj(Versions::parse("3.2.1").unwrap());
+
+        assert_eq!(data.version, Versions::parse("3.2.1").unwrap());
+    }
+
     #[test]
     #[should_panic(expected = "Invalid usage: Authors can not be empty!")]
     fn with_authors_should_panic_on_empty_vector() {
@@ -336,4 +749,182 @@ mod tests {
 
         assert_eq!(data.description(), "My awesome description");
     }
+
+    #[test]
+    fn merge_should_override_scalar_values_set_in_other() {
+        let mut data = ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+        data.set_title("Base title");
+        data.set_copyright("Base copyright");
+        data.set_description_str("Base description");
+        let mut other = ChocolateyMetadata::new();
+        other.set_title("Overlay title");
+        other.version = Versions::parse("2.1.0").unwrap();
+        other.require_license_acceptance = false;
+        other.issues_url = Some(Url::parse("https://github.com/WormieCorp/aer/issues").unwrap());
+
+        data.merge(&other);
+
+        assert_eq!(data.title, Some("Overlay title".into()));
+        assert_eq!(data.copyright, Some("Base copyright".into()));
+        assert_eq!(data.version, Versions::parse("2.1.0").unwrap());
+        assert_eq!(data.authors(), ["AdmiringWorm"]);
+        assert_eq!(data.description(), "Base description");
+        assert!(!data.require_license_acceptance);
+        assert!(data.lowercase_id());
+        assert_eq!(
+            data.issues_url,
+            Some(Url::parse("https://github.com/WormieCorp/aer/issues").unwrap())
+        );
+    }
+
+    #[test]
+    fn merge_should_keep_values_when_other_is_default() {
+        let mut expected = ChocolateyMetadata::with_authors(&["AdmiringWorm"]);
+        expected.set_title("Base title");
+        expected.version = Versions::parse("1.0.0").unwrap();
+        expected.set_tags(&["base"]);
+        let mut data = expected.clone();
+
+        data.merge(&ChocolateyMetadata::new());
+
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn add_tag_should_append_new_tag() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli"]);
+
+        data.add_tag("development");
+
+        assert_eq!(data.tags, ["cli", "development"]);
+    }
+
+    #[test]
+    fn add_tag_should_not_add_duplicate_tag() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli"]);
+
+        data.add_tag("cli");
+
+        assert_eq!(data.tags, ["cli"]);
+    }
+
+    #[test]
+    fn add_tags_should_append_to_existing_tags() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli", "development"]);
+
+        data.add_tags(&["development", "formatter", "formatter"]);
+
+        assert_eq!(data.tags, ["cli", "development", "formatter"]);
+    }
+
+    #[test]
+    fn add_tags_should_accept_owned_strings() {
+        let mut data = ChocolateyMetadata::new();
+
+        data.add_tags(&["cli".to_owned(), "development".to_owned()]);
+
+        assert_eq!(data.tags, ["cli", "development"]);
+    }
+
+    #[test]
+    fn normalize_tags_should_lowercase_and_deduplicate_tags() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["CLI", "development", "Development", "MarkDown"]);
+
+        let changed = data.normalize_tags();
+
+        assert!(changed);
+        assert_eq!(data.tags, ["cli", "development", "markdown"]);
+    }
+
+    #[test]
+    fn normalize_tags_should_return_false_on_lowercase_tags() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli", "development"]);
+
+        let changed = data.normalize_tags();
+
+        assert!(!changed);
+        assert_eq!(data.tags, ["cli", "development"]);
+    }
+
+    #[test]
+    fn remove_tag_should_remove_existing_tag() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli", "development", "formatter"]);
+
+        let removed = data.remove_tag("development");
+
+        assert!(removed);
+        assert_eq!(data.tags, ["cli", "formatter"]);
+    }
+
+    #[test]
+    fn remove_tag_should_return_false_on_missing_tag() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli"]);
+
+        let removed = data.remove_tag(String::from("development"));
+
+        assert!(!removed);
+        assert_eq!(data.tags, ["cli"]);
+    }
+
+    #[rstest(
+        tags,
+        case("cli development formatter"),
+        case("cli  development   formatter"),
+        case("  cli development formatter  "),
+        case("\tcli\ndevelopment formatter cli")
+    )]
+    fn set_tags_str_should_split_tags_on_whitespace(tags: &str) {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["existing"]);
+
+        data.set_tags_str(tags);
+
+        assert_eq!(data.tags, ["cli", "development", "formatter"]);
+    }
+
+    #[test]
+    fn set_tags_str_should_clear_tags_on_empty_string() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["existing"]);
+
+        data.set_tags_str(String::from("   "));
+
+        assert!(data.tags.is_empty());
+    }
+
+    #[test]
+    fn merge_should_create_union_of_tags_and_dependencies() {
+        let mut data = ChocolateyMetadata::new();
+        data.set_tags(&["cli", "development"]);
+        data.add_dependencies("chocolatey-core.extension", "1.3.3");
+        data.add_dependencies("dotnet4.5", "4.5.0");
+        let mut other = ChocolateyMetadata::new();
+        other.set_tags(&["development", "formatter"]);
+        other.add_dependencies("chocolatey-core.extension", "1.3.5");
+        other.add_dependencies("vcredist140", "14.0.0");
+
+        data.merge(&other);
+
+        assert_eq!(data.tags, ["cli", "development", "formatter"]);
+        assert_eq!(data.dependencies, {
+            let mut map = HashMap::new();
+            map.insert(
+                "chocolatey-core.extension".to_string(),
+                Versions::parse("1.3.5").unwrap(),
+            );
+            map.insert("dotnet4.5".to_string(), Versions::parse("4.5.0").unwrap());
+            map.insert(
+                "vcredist140".to_string(),
+                Versions::parse("14.0.0").unwrap(),
+            );
+            map
+        });
+    }
 }
@@ -0,0 +1,40 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+use std::error::Error;
+use std::fmt;
+
+use aer_version::SemanticVersionError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataError {
+    /// No authors were specified, at least a single author is required.
+    EmptyAuthors,
+    /// The specified version could not be parsed.
+    InvalidVersion {
+        /// The version that failed to be parsed.
+        version: String,
+        /// The error that was returned when parsing the version.
+        error: SemanticVersionError,
+    },
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetadataError::EmptyAuthors => write!(f, "Authors can not be empty!"),
+            MetadataError::InvalidVersion { version, error } => {
+                write!(f, "The version '{}' is not valid: {}", version, error)
+            }
+        }
+    }
+}
+
+impl Error for MetadataError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MetadataError::InvalidVersion { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
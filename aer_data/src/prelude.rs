@@ -2,9 +2,10 @@
 // Licensed under the MIT license. See LICENSE.txt file in the project
 
 pub use aer_license::LicenseType;
-pub use aer_version::{FixVersion, SemVersion, Versions};
+pub use aer_version::{FixVersion, SemVersion, SemanticVersionError, Versions};
 pub use url::Url;
 
+pub use crate::metadata::errors::MetadataError;
 pub use crate::metadata::{Description, PackageMetadata};
 pub use crate::updater::PackageUpdateData;
 pub use crate::PackageData;
@@ -17,6 +18,6 @@ pub mod chocolatey {
 
     pub use crate::metadata::chocolatey::ChocolateyMetadata;
     pub use crate::updater::chocolatey::{
-        ChocolateyParseUrl, ChocolateyUpdaterData, ChocolateyUpdaterType,
+        ChocolateyInstallerKind, ChocolateyParseUrl, ChocolateyUpdaterData, ChocolateyUpdaterType,
     };
 }
@@ -23,14 +23,132 @@ impl Default for ChocolateyUpdaterType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize), serde(untagged))]
+/// The kind of installer that is used when the updater type is
+/// [ChocolateyUpdaterType::Installer], as msi installers are handled
+/// differently from executable installers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
+pub enum ChocolateyInstallerKind {
+    Exe,
+    Msi,
+}
+
+impl ChocolateyInstallerKind {
+    /// Returns the file type that is passed on to the Chocolatey install
+    /// helpers (*ie: `Install-ChocolateyPackage -FileType`*).
+    pub fn file_type(&self) -> &'static str {
+        match self {
+            ChocolateyInstallerKind::Exe => "exe",
+            ChocolateyInstallerKind::Msi => "msi",
+        }
+    }
+
+    /// Returns the silent arguments that are commonly used by the installer
+    /// kind, or `None` if the arguments depend on the actual installer.
+    pub fn default_silent_args(&self) -> Option<&'static str> {
+        match self {
+            ChocolateyInstallerKind::Exe => None,
+            ChocolateyInstallerKind::Msi => Some("/qn /norestart"),
+        }
+    }
+}
+
+impl Default for ChocolateyInstallerKind {
+    fn default() -> Self {
+        Self::Exe
+    }
+}
+
+/// The url that should be parsed to find the latest version of the software,
+/// optionally with a regex to use when parsing the url.
+///
+/// A [ChocolateyParseUrl::UrlWithRegex] with an empty regex is considered to be
+/// the same as a [ChocolateyParseUrl::Url] without a regex, see
+/// [normalize](ChocolateyParseUrl::normalize). The parse url is always
+/// normalized when it is deserialized or serialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(Deserialize, Serialize),
+    serde(from = "RawChocolateyParseUrl", into = "RawChocolateyParseUrl")
+)]
 pub enum ChocolateyParseUrl {
     UrlWithRegex { url: Url, regex: String },
     Url(Url),
 }
 
-#[derive(Debug, Default, Clone, PartialEq)]
+/// The parse url as it is stored, only used to normalize the parse url when it
+/// is deserialized or serialized.
+#[cfg(feature = "serialize")]
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawChocolateyParseUrl {
+    UrlWithRegex { url: Url, regex: String },
+    Url(Url),
+}
+
+#[cfg(feature = "serialize")]
+impl From<RawChocolateyParseUrl> for ChocolateyParseUrl {
+    fn from(raw: RawChocolateyParseUrl) -> Self {
+        match raw {
+            RawChocolateyParseUrl::UrlWithRegex { url, regex } => {
+                ChocolateyParseUrl::UrlWithRegex { url, regex }
+            }
+            RawChocolateyParseUrl::Url(url) => ChocolateyParseUrl::Url(url),
+        }
+        .normalize()
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl From<ChocolateyParseUrl> for RawChocolateyParseUrl {
+    fn from(parse_url: ChocolateyParseUrl) -> Self {
+        match parse_url.normalize() {
+            ChocolateyParseUrl::UrlWithRegex { url, regex } => {
+                RawChocolateyParseUrl::UrlWithRegex { url, regex }
+            }
+            ChocolateyParseUrl::Url(url) => RawChocolateyParseUrl::Url(url),
+        }
+    }
+}
+
+impl ChocolateyParseUrl {
+    /// Returns the url that should be parsed, regardless of which variant is
+    /// used.
+    pub fn url(&self) -> &Url {
+        match self {
+            ChocolateyParseUrl::UrlWithRegex { url, .. } | ChocolateyParseUrl::Url(url) => url,
+        }
+    }
+
+    /// Returns the regex that should be used when parsing the url, or `None`
+    /// if no regex (or an empty regex) have been specified.
+    pub fn regex(&self) -> Option<&str> {
+        match self {
+            ChocolateyParseUrl::UrlWithRegex { regex, .. } if !regex.is_empty() => Some(regex),
+            _ => None,
+        }
+    }
+
+    /// Returns the canonical form of the parse url, where a url with an empty
+    /// regex is converted to a [ChocolateyParseUrl::Url].
+    pub fn normalize(self) -> ChocolateyParseUrl {
+        match self {
+            ChocolateyParseUrl::UrlWithRegex { url, regex } if regex.is_empty() => {
+                ChocolateyParseUrl::Url(url)
+            }
+            parse_url => parse_url,
+        }
+    }
+}
+
+impl PartialEq for ChocolateyParseUrl {
+    fn eq(&self, other: &ChocolateyParseUrl) -> bool {
+        self.url() == other.url() && self.regex() == other.regex()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Deserialize, Serialize))]
 #[non_exhaustive]
 pub struct ChocolateyUpdaterData {
@@ -38,6 +156,25 @@ pub struct ChocolateyUpdaterData {
     pub embedded: bool,
     #[cfg_attr(feature = "serialize", serde(default, rename = "type"))]
     pub updater_type: ChocolateyUpdaterType,
+
+    /// The kind of installer to use, only used when the updater type is
+    /// [ChocolateyUpdaterType::Installer].
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub installer_kind: ChocolateyInstallerKind,
+
+    /// The arguments that should be passed to the installer to make it run
+    /// silently.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub silent_args: Option<String>,
+
+    /// The exit codes the installer can return that should be treated as a
+    /// successful installation.
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default = "crate::defaults::valid_exit_codes")
+    )]
+    pub valid_exit_codes: Vec<i32>,
+
     pub parse_url: Option<ChocolateyParseUrl>,
 
     regexes: HashMap<String, String>,
@@ -48,6 +185,9 @@ impl ChocolateyUpdaterData {
         ChocolateyUpdaterData {
             embedded: false,
             updater_type: ChocolateyUpdaterType::default(),
+            installer_kind: ChocolateyInstallerKind::default(),
+            silent_args: None,
+            valid_exit_codes: crate::defaults::valid_exit_codes(),
             parse_url: None,
             regexes: HashMap::new(),
         }
@@ -66,6 +206,12 @@ impl ChocolateyUpdaterData {
     }
 }
 
+impl Default for ChocolateyUpdaterData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,6 +221,9 @@ mod tests {
         let expected = ChocolateyUpdaterData {
             embedded: false,
             updater_type: ChocolateyUpdaterType::default(),
+            installer_kind: ChocolateyInstallerKind::default(),
+            silent_args: None,
+            valid_exit_codes: vec![0],
             parse_url: None,
             regexes: HashMap::new(),
         };
@@ -106,4 +255,197 @@ mod tests {
 
         assert_eq!(data.regexes(), &expected);
     }
+
+    #[test]
+    fn parse_url_should_return_url_and_no_regex_for_url() {
+        let expected = Url::parse("https://github.com/WormieCorp/aer/releases").unwrap();
+        let parse_url = ChocolateyParseUrl::Url(expected.clone());
+
+        assert_eq!(parse_url.url(), &expected);
+        assert_eq!(parse_url.regex(), None);
+    }
+
+    #[test]
+    fn parse_url_should_return_url_and_regex_for_url_with_regex() {
+        let expected = Url::parse("https://sourceforge.net/projects/astyle/files/astyle/").unwrap();
+        let parse_url = ChocolateyParseUrl::UrlWithRegex {
+            url: expected.clone(),
+            regex: r"astyle( |%20)(?P<version>[\d\.]+)/$".into(),
+        };
+
+        assert_eq!(parse_url.url(), &expected);
+        assert_eq!(
+            parse_url.regex(),
+            Some(r"astyle( |%20)(?P<version>[\d\.]+)/$")
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn installer_arguments_should_round_trip_through_serde() {
+        let mut expected = ChocolateyUpdaterData::new();
+        expected.updater_type = ChocolateyUpdaterType::Installer;
+        expected.silent_args = Some("/VERYSILENT /NORESTART".into());
+        expected.valid_exit_codes = vec![0, 3010];
+
+        let text = serde_json::to_string(&expected).unwrap();
+        let actual: ChocolateyUpdaterData = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn installer_arguments_should_use_defaults_when_not_serialized() {
+        let actual: ChocolateyUpdaterData =
+            serde_json::from_str(r#"{"type":"Installer","parse_url":null,"regexes":{}}"#).unwrap();
+
+        assert_eq!(actual.silent_args, None);
+        assert_eq!(actual.valid_exit_codes, [0]);
+    }
+
+    #[test]
+    fn parse_url_should_return_no_regex_for_empty_regex() {
+        let parse_url = ChocolateyParseUrl::UrlWithRegex {
+            url: Url::parse("https://example.org").unwrap(),
+            regex: String::new(),
+        };
+
+        assert_eq!(parse_url.regex(), None);
+    }
+
+    #[test]
+    fn normalize_should_convert_empty_regex_to_url() {
+        let url = Url::parse("https://example.org").unwrap();
+        let parse_url = ChocolateyParseUrl::UrlWithRegex {
+            url: url.clone(),
+            regex: String::new(),
+        };
+
+        let actual = parse_url.normalize();
+
+        assert!(matches!(actual, ChocolateyParseUrl::Url(ref u) if u == &url));
+    }
+
+    #[test]
+    fn normalize_should_keep_non_empty_regex() {
+        let parse_url = ChocolateyParseUrl::UrlWithRegex {
+            url: Url::parse("https://example.org").unwrap(),
+            regex: "test".into(),
+        };
+
+        let actual = parse_url.clone().normalize();
+
+        assert!(
+            matches!(actual, ChocolateyParseUrl::UrlWithRegex { ref regex, .. } if regex == "test")
+        );
+        assert_eq!(actual, parse_url);
+    }
+
+    #[test]
+    fn parse_url_should_be_equal_to_url_with_empty_regex() {
+        let url = Url::parse("https://example.org").unwrap();
+
+        assert_eq!(
+            ChocolateyParseUrl::UrlWithRegex {
+                url: url.clone(),
+                regex: String::new()
+            },
+            ChocolateyParseUrl::Url(url.clone())
+        );
+        assert_ne!(
+            ChocolateyParseUrl::UrlWithRegex {
+                url: url.clone(),
+                regex: "test".into()
+            },
+            ChocolateyParseUrl::Url(url)
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn parse_url_should_deserialize_empty_regex_as_url() {
+        let actual: ChocolateyParseUrl =
+            serde_json::from_str(r#"{"url":"https://example.org/","regex":""}"#).unwrap();
+
+        match actual {
+            ChocolateyParseUrl::Url(url) => {
+                assert_eq!(url, Url::parse("https://example.org").unwrap())
+            }
+            parse_url => panic!("Expected a normalized url, got {:?}", parse_url),
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn parse_url_should_not_serialize_empty_regex_after_deserializing() {
+        let parse_url: ChocolateyParseUrl =
+            serde_json::from_str(r#"{"url":"https://example.org/","regex":""}"#).unwrap();
+
+        let actual = serde_json::to_string(&parse_url).unwrap();
+
+        assert_eq!(actual, r#""https://example.org/""#);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn parse_url_should_keep_non_empty_regex_when_deserializing() {
+        let text = r#"{"url":"https://example.org/","regex":"\\.exe$"}"#;
+
+        let actual: ChocolateyParseUrl = serde_json::from_str(text).unwrap();
+
+        assert!(matches!(actual, ChocolateyParseUrl::UrlWithRegex { .. }));
+        assert_eq!(actual.regex(), Some("\\.exe$"));
+        assert_eq!(serde_json::to_string(&actual).unwrap(), text);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn parse_url_should_serialize_empty_regex_as_url_string() {
+        let parse_url = ChocolateyParseUrl::UrlWithRegex {
+            url: Url::parse("https://example.org").unwrap(),
+            regex: String::new(),
+        };
+
+        let actual = serde_json::to_string(&parse_url).unwrap();
+
+        assert_eq!(actual, r#""https://example.org/""#);
+    }
+
+    #[test]
+    fn installer_kind_should_return_expected_file_type() {
+        assert_eq!(ChocolateyInstallerKind::Exe.file_type(), "exe");
+        assert_eq!(ChocolateyInstallerKind::Msi.file_type(), "msi");
+    }
+
+    #[test]
+    fn installer_kind_should_return_default_silent_args_for_msi() {
+        assert_eq!(ChocolateyInstallerKind::Exe.default_silent_args(), None);
+        assert_eq!(
+            ChocolateyInstallerKind::Msi.default_silent_args(),
+            Some("/qn /norestart")
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn installer_kind_should_default_to_exe_for_existing_installer_data() {
+        let actual: ChocolateyUpdaterData =
+            serde_json::from_str(r#"{"type":"Installer","parse_url":null,"regexes":{}}"#).unwrap();
+
+        assert_eq!(actual.updater_type, ChocolateyUpdaterType::Installer);
+        assert_eq!(actual.installer_kind, ChocolateyInstallerKind::Exe);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn installer_kind_should_deserialize_msi_installer() {
+        let actual: ChocolateyUpdaterData = serde_json::from_str(
+            r#"{"type":"Installer","installer_kind":"Msi","parse_url":null,"regexes":{}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(actual.updater_type, ChocolateyUpdaterType::Installer);
+        assert_eq!(actual.installer_kind, ChocolateyInstallerKind::Msi);
+    }
 }
@@ -0,0 +1,165 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Contains helpers for detecting the type of archives that have been
+//! downloaded, so the correct extraction logic can be used.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The number of bytes needed to detect all supported archive types, the
+/// `tar` signature is located at offset 257.
+const HEADER_LENGTH: usize = 262;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const GZIP_MAGIC: &[u8] = b"\x1F\x8B";
+const TAR_MAGIC: &[u8] = b"ustar";
+const TAR_MAGIC_OFFSET: usize = 257;
+
+/// The supported types of archives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    SevenZip,
+    Tar,
+    TarGz,
+    /// A gzip compressed file that is not a tar archive (based on the
+    /// extension of the file).
+    Gzip,
+}
+
+impl ArchiveKind {
+    /// Detects the type of archive based on the magic bytes at the start of
+    /// the archive, using the file name to tell a `.tar.gz` archive apart from
+    /// other gzip compressed files.
+    ///
+    /// Returns `None` if the bytes do not match any supported archive type.
+    pub fn from_bytes(header: &[u8], file_name: &str) -> Option<ArchiveKind> {
+        if header.starts_with(ZIP_MAGIC) || header.starts_with(ZIP_EMPTY_MAGIC) {
+            Some(ArchiveKind::Zip)
+        } else if header.starts_with(SEVEN_ZIP_MAGIC) {
+            Some(ArchiveKind::SevenZip)
+        } else if header.starts_with(GZIP_MAGIC) {
+            let file_name = file_name.to_lowercase();
+            if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+                Some(ArchiveKind::TarGz)
+            } else {
+                Some(ArchiveKind::Gzip)
+            }
+        } else if header.get(TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len())
+            == Some(TAR_MAGIC)
+        {
+            Some(ArchiveKind::Tar)
+        } else {
+            None
+        }
+    }
+
+    /// Detects the type of archive the file located at `path` is, by reading
+    /// the magic bytes at the start of the file.
+    ///
+    /// Returns `None` if the file is not a supported archive type.
+    pub fn detect(path: &Path) -> io::Result<Option<ArchiveKind>> {
+        let mut header = Vec::with_capacity(HEADER_LENGTH);
+        File::open(path)?
+            .take(HEADER_LENGTH as u64)
+            .read_to_end(&mut header)?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        Ok(ArchiveKind::from_bytes(&header, &file_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn tar_header() -> Vec<u8> {
+        let mut header = vec![0; 512];
+        header[..8].copy_from_slice(b"test.txt");
+        header[TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + 6].copy_from_slice(b"ustar\0");
+
+        header
+    }
+
+    #[rstest]
+    #[case(b"PK\x03\x04\x14\x00\x00\x00", "test.zip", Some(ArchiveKind::Zip))]
+    #[case(b"PK\x05\x06\x00\x00\x00\x00", "empty.zip", Some(ArchiveKind::Zip))]
+    #[case(b"PK\x03\x04\x14\x00\x00\x00", "test.nupkg", Some(ArchiveKind::Zip))]
+    #[case(b"7z\xBC\xAF\x27\x1C\x00\x04", "test.7z", Some(ArchiveKind::SevenZip))]
+    #[case(b"\x1F\x8B\x08\x00\x00\x00", "test.tar.gz", Some(ArchiveKind::TarGz))]
+    #[case(b"\x1F\x8B\x08\x00\x00\x00", "TEST.TGZ", Some(ArchiveKind::TarGz))]
+    #[case(b"\x1F\x8B\x08\x00\x00\x00", "test.txt.gz", Some(ArchiveKind::Gzip))]
+    #[case(b"MZ\x90\x00\x03\x00", "test.zip", None)]
+    #[case(b"", "test.zip", None)]
+    fn from_bytes_should_detect_archive_kind(
+        #[case] header: &[u8],
+        #[case] file_name: &str,
+        #[case] expected: Option<ArchiveKind>,
+    ) {
+        let actual = ArchiveKind::from_bytes(header, file_name);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn from_bytes_should_detect_tar_archive() {
+        let actual = ArchiveKind::from_bytes(&tar_header(), "test.tar");
+
+        assert_eq!(actual, Some(ArchiveKind::Tar));
+    }
+
+    #[rstest]
+    #[case(
+        "archive-detect-test.7z",
+        b"7z\xBC\xAF\x27\x1C\x00\x04",
+        Some(ArchiveKind::SevenZip)
+    )]
+    #[case(
+        "archive-detect-test.tar.gz",
+        b"\x1F\x8B\x08\x00",
+        Some(ArchiveKind::TarGz)
+    )]
+    #[case("archive-detect-test.bin", b"\x00\x01", None)]
+    fn detect_should_read_archive_kind_from_file(
+        #[case] file_name: &str,
+        #[case] content: &[u8],
+        #[case] expected: Option<ArchiveKind>,
+    ) {
+        let path = std::env::temp_dir().join(file_name);
+        std::fs::write(&path, content).unwrap();
+
+        let actual = ArchiveKind::detect(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn detect_should_read_tar_archive_from_file() {
+        let path = std::env::temp_dir().join("archive-detect-test.tar");
+        std::fs::write(&path, tar_header()).unwrap();
+
+        let actual = ArchiveKind::detect(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(actual, Some(ArchiveKind::Tar));
+    }
+
+    #[test]
+    fn detect_should_return_error_on_missing_file() {
+        let path = std::env::temp_dir().join("archive-detect-missing.zip");
+
+        let actual = ArchiveKind::detect(&path).unwrap_err();
+
+        assert_eq!(actual.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Contains helpers for debugging the configuration of a package, without
+//! needing to run a full update.
+
+use aer_data::prelude::chocolatey::ChocolateyUpdaterData;
+use aer_web::response::parse_version;
+use aer_web::LinkElement;
+use regex::Regex;
+
+/// The result of matching a single named regex against the links on a parsed
+/// page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegexReport {
+    /// The name of the regex, as specified in the updater data.
+    pub name: String,
+    /// The regex pattern that was used.
+    pub pattern: String,
+    /// The error returned when compiling the regex, if it is not valid.
+    pub error: Option<String>,
+    /// The links that matched the regex, with the version set to the version
+    /// captured in the group named `version` (if any).
+    pub matches: Vec<LinkElement>,
+}
+
+/// Matches every regex specified in the chocolatey updater data against the
+/// specified links (*ie: the links returned when reading a html response*),
+/// and reports which links matched and the version that was extracted.
+///
+/// The reports are ordered by the name of the regex. The captured versions are
+/// parsed the same way as when parsing a web page, and a leading `v` or `V` is
+/// only removed when `strip_v_prefix` is set (*the same option as
+/// [HtmlResponse::set_strip_v_prefix](aer_web::response::HtmlResponse::set_strip_v_prefix)*).
+pub fn diagnose_regexes(
+    links: &[LinkElement],
+    data: &ChocolateyUpdaterData,
+    strip_v_prefix: bool,
+) -> Vec<RegexReport> {
+    let mut regexes: Vec<_> = data.regexes().iter().collect();
+    regexes.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    regexes
+        .into_iter()
+        .map(|(name, pattern)| {
+            let mut report = RegexReport {
+                name: name.clone(),
+                pattern: pattern.clone(),
+                error: None,
+                matches: Vec::new(),
+            };
+
+            match Regex::new(pattern) {
+                Ok(re) => report.matches = match_links(links, &re, strip_v_prefix),
+                Err(err) => report.error = Some(err.to_string()),
+            }
+
+            report
+        })
+        .collect()
+}
+
+fn match_links(links: &[LinkElement], re: &Regex, strip_v_prefix: bool) -> Vec<LinkElement> {
+    links
+        .iter()
+        .filter_map(|link| {
+            let captures = re.captures(link.link.as_str())?;
+            let mut link = link.clone();
+            link.version = parse_version(captures, strip_v_prefix);
+
+            Some(link)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use aer_data::prelude::{Url, Versions};
+    use aer_web::LinkType;
+
+    use super::*;
+
+    fn sample_links() -> Vec<LinkElement> {
+        [
+            "https://github.com/codecov/codecov-exe/releases/download/v1.13.0/codecov-win7-x86.zip",
+            "https://github.com/codecov/codecov-exe/releases/download/v1.13.0/codecov-win7-x64.zip",
+            "https://github.com/codecov/codecov-exe/releases/download/v1.13.0/Codecov.1.13.0.nupkg",
+            "https://github.com/codecov/codecov-exe/releases/tag/v1.13.0",
+        ]
+        .iter()
+        .map(|link| LinkElement::new(Url::parse(link).unwrap(), LinkType::Unknown))
+        .collect()
+    }
+
+    #[test]
+    fn diagnose_regexes_should_report_matching_links_and_versions() {
+        let mut data = ChocolateyUpdaterData::new();
+        data.add_regex("arch32", r"/v(?P<version>[\d\.]+)/codecov-win7-x86\.zip$");
+        data.add_regex("arch64", r"/(?P<version>v[\d\.]+)/codecov-win7-x64\.zip$");
+        data.add_regex("release", r"/releases/download/");
+        let links = sample_links();
+
+        let reports = diagnose_regexes(&links, &data, true);
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].name, "arch32");
+        assert_eq!(reports[0].error, None);
+        assert_eq!(reports[0].matches.len(), 1);
+        assert_eq!(reports[0].matches[0].link, links[0].link);
+        assert_eq!(
+            reports[0].matches[0].version,
+            Some(Versions::parse("1.13.0").unwrap())
+        );
+        assert_eq!(reports[1].name, "arch64");
+        assert_eq!(reports[1].matches.len(), 1);
+        assert_eq!(reports[1].matches[0].link, links[1].link);
+        assert_eq!(
+            reports[1].matches[0].version,
+            Some(Versions::parse("1.13.0").unwrap())
+        );
+        assert_eq!(reports[2].name, "release");
+        assert_eq!(reports[2].matches.len(), 3);
+        assert!(reports[2].matches.iter().all(|link| link.version.is_none()));
+    }
+
+    #[test]
+    fn diagnose_regexes_should_keep_v_prefix_when_not_stripping() {
+        let mut data = ChocolateyUpdaterData::new();
+        data.add_regex("arch64", r"/(?P<version>v[\d\.]+)/codecov-win7-x64\.zip$");
+        let links = sample_links();
+
+        let reports = diagnose_regexes(&links, &data, false);
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].matches.len(), 1);
+        assert_eq!(reports[0].matches[0].link, links[1].link);
+        assert_eq!(reports[0].matches[0].version, None);
+    }
+
+    #[test]
+    fn diagnose_regexes_should_report_no_matches() {
+        let mut data = ChocolateyUpdaterData::new();
+        data.add_regex("installer", r"\.msi$");
+
+        let reports = diagnose_regexes(&sample_links(), &data, true);
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].error, None);
+        assert!(reports[0].matches.is_empty());
+    }
+
+    #[test]
+    fn diagnose_regexes_should_report_invalid_regex() {
+        let mut data = ChocolateyUpdaterData::new();
+        data.add_regex("invalid", r"(?P<version>[\d\.]+");
+
+        let reports = diagnose_regexes(&sample_links(), &data, true);
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].pattern, r"(?P<version>[\d\.]+");
+        assert!(reports[0].error.is_some());
+        assert!(reports[0].matches.is_empty());
+    }
+}
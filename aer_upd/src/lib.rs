@@ -13,6 +13,9 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod archives;
+pub mod diagnostics;
+pub mod packers;
 pub mod parsers;
 pub mod runners;
 
@@ -22,5 +25,8 @@ pub mod data {
 
 pub mod web {
     pub use aer_web::response::ResponseType;
-    pub use aer_web::{errors, LinkElement, LinkType, WebRequest, WebResponse};
+    pub use aer_web::{
+        errors, filter_by_extension, latest_by_version, ConditionalRequest, LinkElement, LinkType,
+        LinkTypeMapping, WebRequest, WebResponse,
+    };
 }
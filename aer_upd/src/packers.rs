@@ -0,0 +1,9 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Contains the functionality for creating the final package files out of the
+//! content of a work directory.
+
+pub mod errors;
+#[cfg(feature = "nupkg")]
+pub mod nupkg;
@@ -0,0 +1,38 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum PackError {
+    /// The file that is required to create the package could not be found.
+    MissingFile(PathBuf),
+    Io(std::io::Error),
+    Other {
+        inner: Box<dyn Error>,
+    },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::MissingFile(path) => write!(
+                f,
+                "The required file '{}' could not be found!",
+                path.display()
+            ),
+            PackError::Io(err) => err.fmt(f),
+            PackError::Other { inner } => inner.fmt(f),
+        }
+    }
+}
+
+impl Error for PackError {}
+
+impl From<std::io::Error> for PackError {
+    fn from(err: std::io::Error) -> Self {
+        PackError::Io(err)
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+#![cfg_attr(docsrs, doc(cfg(feature = "nupkg")))]
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use aer_data::prelude::Versions;
+use log::{debug, info};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::packers::errors::PackError;
+
+const CONTENT_TYPES_NAME: &str = "[Content_Types].xml";
+const RELATIONSHIPS_NAME: &str = "_rels/.rels";
+const MANIFEST_RELATIONSHIP: &str = "http://schemas.microsoft.com/packaging/2010/07/manifest";
+
+/// Creates a Chocolatey package (`{id}.{version}.nupkg`) in the output
+/// directory, containing all files available in the work directory.
+///
+/// The work directory is expected to contain the `{id}.nuspec` file at its
+/// root, and the necessary `_rels/.rels` and `[Content_Types].xml` entries
+/// will be generated when creating the package. Any existing `.nupkg` files in
+/// the work directory are not included, and any existing `_rels/.rels` or
+/// `[Content_Types].xml` file is replaced by the generated entry.
+///
+/// ## Returns
+///
+/// The path to the created package on success.
+pub fn pack(
+    work_dir: &Path,
+    output_dir: &Path,
+    id: &str,
+    version: &Versions,
+) -> Result<PathBuf, PackError> {
+    let nuspec_name = format!("{}.nuspec", id);
+    if !work_dir.join(&nuspec_name).is_file() {
+        return Err(PackError::MissingFile(work_dir.join(nuspec_name)));
+    }
+
+    let output = output_dir.join(format!("{}.{}.nupkg", id, version.to_choco()));
+    info!("Creating package '{}'", output.display());
+
+    let mut files = vec![];
+    collect_files(work_dir, work_dir, &mut files)?;
+
+    let mut zip = ZipWriter::new(File::create(&output)?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, path) in &files {
+        debug!("Adding '{}' to the package", name);
+        zip.start_file(name.as_str(), options).map_err(zip_error)?;
+        io::copy(&mut File::open(path)?, &mut zip)?;
+    }
+
+    zip.start_file(RELATIONSHIPS_NAME, options)
+        .map_err(zip_error)?;
+    zip.write_all(relationships(&nuspec_name).as_bytes())?;
+    zip.start_file(CONTENT_TYPES_NAME, options)
+        .map_err(zip_error)?;
+    zip.write_all(content_types(&files).as_bytes())?;
+    zip.finish().map_err(zip_error)?;
+
+    info!("Successfully created package '{}'", output.display());
+
+    Ok(output)
+}
+
+fn zip_error(err: zip::result::ZipError) -> PackError {
+    PackError::Other {
+        inner: Box::new(err),
+    }
+}
+
+/// Collects all files in the specified directory recursively, together with
+/// the name the file should have inside the package.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<(String, PathBuf)>,
+) -> Result<(), PackError> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "nupkg") {
+            continue;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let name = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            if name == RELATIONSHIPS_NAME || name == CONTENT_TYPES_NAME {
+                debug!("Skipping '{}', the entry will be generated", name);
+                continue;
+            }
+
+            files.push((name, path));
+        }
+    }
+
+    Ok(())
+}
+
+fn relationships(nuspec_name: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Type="{}" Target="/{}" Id="R0" />
+</Relationships>"#,
+        MANIFEST_RELATIONSHIP, nuspec_name
+    )
+}
+
+fn content_types(files: &[(String, PathBuf)]) -> String {
+    let mut extensions = BTreeSet::new();
+    extensions.insert("rels".to_owned());
+    let mut overrides = vec![];
+
+    for (name, path) in files {
+        if let Some(ext) = path.extension() {
+            extensions.insert(ext.to_string_lossy().to_lowercase());
+        } else {
+            overrides.push(name);
+        }
+    }
+
+    let mut text = String::from(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    );
+    for ext in extensions {
+        let content_type = if ext == "rels" {
+            "application/vnd.openxmlformats-package.relationships+xml"
+        } else {
+            "application/octet"
+        };
+        text.push_str(&format!(
+            "\n  <Default Extension=\"{}\" ContentType=\"{}\" />",
+            ext, content_type
+        ));
+    }
+    for name in overrides {
+        text.push_str(&format!(
+            "\n  <Override PartName=\"/{}\" ContentType=\"application/octet\" />",
+            name
+        ));
+    }
+    text.push_str("\n</Types>");
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use aer_data::prelude::SemVersion;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn create_work_dir(name: &str) -> PathBuf {
+        let work_dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&work_dir);
+        std::fs::create_dir_all(work_dir.join("tools")).unwrap();
+        std::fs::write(
+            work_dir.join("test-package.nuspec"),
+            "<?xml version=\"1.0\"?><package />",
+        )
+        .unwrap();
+        std::fs::write(
+            work_dir.join("tools").join("chocolateyInstall.ps1"),
+            "Write-Host 'Installing'",
+        )
+        .unwrap();
+
+        work_dir
+    }
+
+    fn entry_names(path: &Path) -> Vec<String> {
+        let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+
+        (0..archive.len())
+            .map(|i| archive.by_index(i).unwrap().name().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn pack_should_create_nupkg_with_expected_entries() {
+        let work_dir = create_work_dir("aer-pack-test");
+        let version = Versions::SemVer(SemVersion::new(1, 2, 0));
+
+        let path = pack(&work_dir, &work_dir, "test-package", &version).unwrap();
+
+        assert_eq!(path, work_dir.join("test-package.1.2.0.nupkg"));
+        assert!(path.is_file());
+        assert_eq!(
+            entry_names(&path),
+            [
+                "test-package.nuspec",
+                "tools/chocolateyInstall.ps1",
+                "_rels/.rels",
+                "[Content_Types].xml"
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn pack_should_not_duplicate_generated_entries() {
+        let work_dir = create_work_dir("aer-pack-existing-test");
+        std::fs::create_dir_all(work_dir.join("_rels")).unwrap();
+        std::fs::write(work_dir.join("_rels").join(".rels"), "<Relationships />").unwrap();
+        std::fs::write(work_dir.join("[Content_Types].xml"), "<Types />").unwrap();
+        let version = Versions::SemVer(SemVersion::new(1, 2, 0));
+
+        let path = pack(&work_dir, &work_dir, "test-package", &version).unwrap();
+
+        assert_eq!(
+            entry_names(&path),
+            [
+                "test-package.nuspec",
+                "tools/chocolateyInstall.ps1",
+                "_rels/.rels",
+                "[Content_Types].xml"
+            ]
+        );
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name("_rels/.rels")
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        assert!(text.contains("Target=\"/test-package.nuspec\""));
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn pack_should_reference_nuspec_in_relationships() {
+        let work_dir = create_work_dir("aer-pack-rels-test");
+        let version = Versions::SemVer(SemVersion::new(1, 0, 0));
+
+        let path = pack(&work_dir, &work_dir, "test-package", &version).unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name("_rels/.rels")
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        assert!(text.contains("Target=\"/test-package.nuspec\""));
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn pack_should_return_error_on_missing_nuspec() {
+        let work_dir = create_work_dir("aer-pack-missing-test");
+        let version = Versions::SemVer(SemVersion::new(1, 0, 0));
+
+        let err = pack(&work_dir, &work_dir, "other-package", &version).unwrap_err();
+
+        match err {
+            PackError::MissingFile(path) => {
+                assert_eq!(path, work_dir.join("other-package.nuspec"))
+            }
+            err => panic!("Unexpected error returned: {}", err),
+        }
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+}
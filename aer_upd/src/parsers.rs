@@ -9,6 +9,7 @@ use aer_data::prelude::*;
 use log::warn;
 
 pub mod errors;
+pub mod install_script;
 #[cfg(feature = "toml_data")]
 pub mod toml;
 
@@ -79,3 +80,121 @@ pub fn read_file(path: &Path) -> Result<PackageData, errors::ParserError> {
 
     Err(errors::ParserError::NoParsers(path.to_owned()))
 }
+
+/// Expands any `${VAR}` occurrences in the specified text with the value of
+/// the environment variable with the same name. Any variable that is not set
+/// in the current environment is left as-is.
+///
+/// A literal `${` can be kept by escaping it as `$${` (*ie: `$${VAR}` becomes
+/// `${VAR}` without being expanded*).
+#[cfg_attr(not(feature = "toml_data"), allow(dead_code))]
+pub(crate) fn expand_env_vars(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("${");
+            rest = after;
+            continue;
+        }
+
+        result.push_str(&rest[..start]);
+
+        match after.find('}') {
+            Some(end) if is_variable_name(&after[..end]) => {
+                let name = &after[..end];
+                if let Ok(value) = std::env::var(name) {
+                    result.push_str(&value);
+                } else {
+                    warn!("The environment variable '{}' is not set!", name);
+                    result.push_str(&rest[start..start + end + 3]);
+                }
+
+                rest = &after[end + 1..];
+            }
+            _ => {
+                result.push_str("${");
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+
+    result
+}
+
+#[cfg_attr(not(feature = "toml_data"), allow(dead_code))]
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest(
+        text,
+        expected,
+        case("${AER_PARSERS_TEST_VAR}", "expanded"),
+        case("v${AER_PARSERS_TEST_VAR}/file", "vexpanded/file"),
+        case("${AER_PARSERS_TEST_VAR}-${AER_PARSERS_TEST_VAR}", "expanded-expanded"),
+        case("no variables", "no variables")
+    )]
+    fn expand_env_vars_should_expand_set_variables(text: &str, expected: &str) {
+        std::env::set_var("AER_PARSERS_TEST_VAR", "expanded");
+
+        let actual = expand_env_vars(text);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest(
+        text,
+        expected,
+        case("$${AER_PARSERS_TEST_VAR}", "${AER_PARSERS_TEST_VAR}"),
+        case(
+            "v$${AER_PARSERS_TEST_VAR}-${AER_PARSERS_TEST_VAR}",
+            "v${AER_PARSERS_TEST_VAR}-expanded"
+        ),
+        case(r"^\d$${2}$", r"^\d${2}$"),
+        case("$$", "$$")
+    )]
+    fn expand_env_vars_should_not_expand_escaped_variables(text: &str, expected: &str) {
+        std::env::set_var("AER_PARSERS_TEST_VAR", "expanded");
+
+        let actual = expand_env_vars(text);
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest(
+        text,
+        case("${AER_PARSERS_TEST_UNSET_VAR}"),
+        case("before ${AER_PARSERS_TEST_UNSET_VAR} after"),
+        case("${not valid}"),
+        case("${}"),
+        case("${UNCLOSED"),
+        case("$AER_PARSERS_TEST_VAR")
+    )]
+    fn expand_env_vars_should_leave_unset_or_invalid_variables(text: &str) {
+        std::env::remove_var("AER_PARSERS_TEST_UNSET_VAR");
+
+        let actual = expand_env_vars(text);
+
+        assert_eq!(actual, text);
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Best-effort parsing of an existing `chocolateyInstall.ps1` script, used to
+//! recover the previous download urls and checksums when importing an existing
+//! package.
+
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref ASSIGNMENT_RE: Regex = Regex::new(
+        r#"(?im)(?:^|[\s;{(])\$?(url|url64|url64bit|checksum|checksum64)\s*=\s*(?:'([^']*)'|"([^"]*)")"#,
+    )
+    .unwrap();
+}
+
+/// The values that could be extracted from an install script, any value that
+/// could not be found is set to `None`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InstallScriptValues {
+    /// The url of the 32bit (or architecture independent) file.
+    pub url: Option<String>,
+    /// The url of the 64bit file (*`url64bit` or `url64`*).
+    pub url64: Option<String>,
+    /// The checksum of the 32bit (or architecture independent) file.
+    pub checksum: Option<String>,
+    /// The checksum of the 64bit file.
+    pub checksum64: Option<String>,
+}
+
+/// Extracts the urls and checksums assigned in the specified install script,
+/// both variable assignments (*ie: `$url = '...'`*) and hashtable entries (*ie:
+/// `url64bit = '...'`*) are supported. Only values assigned to a string literal
+/// are extracted, and the first assignment of every value is used.
+///
+/// Assignments inside of comments (*ie: `# url = '...'` or `<# ... #>`*) are
+/// ignored.
+pub fn parse_install_script(text: &str) -> InstallScriptValues {
+    let text = strip_comments(text);
+    let mut values = InstallScriptValues::default();
+
+    for capture in ASSIGNMENT_RE.captures_iter(&text) {
+        let value = match capture.get(2).or_else(|| capture.get(3)) {
+            Some(value) if !value.as_str().trim().is_empty() => value.as_str().trim(),
+            _ => continue,
+        };
+
+        let field = match capture[1].to_lowercase().as_str() {
+            "url" => &mut values.url,
+            "url64" | "url64bit" => &mut values.url64,
+            "checksum" => &mut values.checksum,
+            "checksum64" => &mut values.checksum64,
+            _ => continue,
+        };
+
+        if field.is_none() {
+            *field = Some(value.into());
+        }
+    }
+
+    values
+}
+
+/// Removes both line comments and block comments from the specified script,
+/// while keeping any `#` character that is part of a quoted string.
+fn strip_comments(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut quote = None;
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), _) if q == c => quote = None,
+            (Some(_), _) => {}
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '<') if chars.peek() == Some(&'#') => {
+                let mut previous = chars.next();
+                for c in chars.by_ref() {
+                    if previous == Some('#') && c == '>' {
+                        break;
+                    }
+                    if c == '\n' {
+                        result.push(c);
+                    }
+                    previous = Some(c);
+                }
+                continue;
+            }
+            (None, '#') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push(c);
+                        break;
+                    }
+                }
+                continue;
+            }
+            (None, _) => {}
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+/// Reads the install script located at the specified path, and extracts the
+/// urls and checksums (see [parse_install_script]).
+pub fn read_install_script(path: &Path) -> std::io::Result<InstallScriptValues> {
+    let text = std::fs::read_to_string(path)?;
+
+    Ok(parse_install_script(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_install_script_should_extract_values_from_package_args() {
+        let script = r#"$ErrorActionPreference = 'Stop';
+$toolsDir   = "$(Split-Path -parent $MyInvocation.MyCommand.Definition)"
+
+$packageArgs = @{
+  packageName   = $env:ChocolateyPackageName
+  unzipLocation = $toolsDir
+  fileType      = 'exe'
+  url           = 'https://example.org/downloads/test-1.0.0-x86.exe'
+  url64bit      = 'https://example.org/downloads/test-1.0.0-x64.exe'
+
+  softwareName  = 'test*'
+
+  checksum      = 'B1E0B8F1E0A1B2C3D4E5F60718293A4B5C6D7E8F90A1B2C3D4E5F60718293A4B'
+  checksumType  = 'sha256'
+  checksum64    = "c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4bb1e0b8f1e0a1b2"
+  checksumType64= 'sha256'
+
+  silentArgs    = '/S'
+}
+
+Install-ChocolateyPackage @packageArgs
+"#;
+
+        let actual = parse_install_script(script);
+
+        assert_eq!(
+            actual,
+            InstallScriptValues {
+                url: Some("https://example.org/downloads/test-1.0.0-x86.exe".into()),
+                url64: Some("https://example.org/downloads/test-1.0.0-x64.exe".into()),
+                checksum: Some(
+                    "B1E0B8F1E0A1B2C3D4E5F60718293A4B5C6D7E8F90A1B2C3D4E5F60718293A4B".into()
+                ),
+                checksum64: Some(
+                    "c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4bb1e0b8f1e0a1b2".into()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_install_script_should_extract_values_from_variables() {
+        let script = r#"$packageName = 'test'
+$url = "https://example.org/test.zip"
+$checksum = '5d41402abc4b2a76b9719d911017c592'
+$url64 = $url
+
+Install-ChocolateyZipPackage $packageName $url "$(Split-Path -parent $MyInvocation.MyCommand.Definition)" -Checksum $checksum
+"#;
+
+        let actual = parse_install_script(script);
+
+        assert_eq!(
+            actual,
+            InstallScriptValues {
+                url: Some("https://example.org/test.zip".into()),
+                checksum: Some("5d41402abc4b2a76b9719d911017c592".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_install_script_should_use_first_assignment_and_inline_hashtables() {
+        let script = "$packageArgs = @{ url = 'https://example.org/first.msi'; checksum = 'abc' }\n\
+                      $url = 'https://example.org/second.msi'\n\
+                      $checksum = 'def'";
+
+        let actual = parse_install_script(script);
+
+        assert_eq!(actual.url, Some("https://example.org/first.msi".into()));
+        assert_eq!(actual.checksum, Some("abc".into()));
+    }
+
+    #[test]
+    fn parse_install_script_should_ignore_commented_assignments() {
+        let script = r#"# url = 'https://example.org/line-comment.msi'
+<#
+  url64bit = 'https://example.org/block-comment.msi'
+#>
+$packageArgs = @{
+  url      = 'https://example.org/test.msi#fragment' # url = 'https://example.org/trailing.msi'
+  url64bit = "https://example.org/test-x64.msi"
+  # checksum = 'commented'
+  checksum = 'abc'
+}
+"#;
+
+        let actual = parse_install_script(script);
+
+        assert_eq!(
+            actual,
+            InstallScriptValues {
+                url: Some("https://example.org/test.msi#fragment".into()),
+                url64: Some("https://example.org/test-x64.msi".into()),
+                checksum: Some("abc".into()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_install_script_should_return_empty_values_without_assignments() {
+        let actual = parse_install_script("Write-Host 'Nothing to see here'");
+
+        assert_eq!(actual, InstallScriptValues::default());
+    }
+
+    #[test]
+    fn read_install_script_should_return_error_on_missing_file() {
+        let path = std::env::temp_dir().join("missing-chocolateyInstall.ps1");
+
+        let actual = read_install_script(&path).unwrap_err();
+
+        assert_eq!(actual.kind(), std::io::ErrorKind::NotFound);
+    }
+}
@@ -9,7 +9,7 @@ use std::path::Path;
 use aer_data::PackageData;
 use log::{debug, error};
 
-use crate::parsers::{errors, DataReader};
+use crate::parsers::{errors, expand_env_vars, DataReader};
 
 pub struct TomlParser;
 
@@ -43,7 +43,11 @@ impl DataReader for TomlParser {
             }
 
             debug!("Deserializing TOML Package data");
-            match toml::from_str(&config_text) {
+            let result = toml::from_str(&config_text).and_then(|mut value| {
+                expand_values(&mut value);
+                toml::Value::try_into(value)
+            });
+            match result {
                 Err(err) => {
                     error!("Failed to deserialize package data: {:?}", err);
                     let fmt = err.to_string();
@@ -59,6 +63,18 @@ impl DataReader for TomlParser {
     }
 }
 
+/// Expands environment variables in all string values, before the values are
+/// deserialized to the actual types. Values that need to contain a literal
+/// `${` (*ie: regexes*) can escape it as `$${`.
+fn expand_values(value: &mut toml::Value) {
+    match value {
+        toml::Value::String(text) => *text = expand_env_vars(text),
+        toml::Value::Array(values) => values.iter_mut().for_each(expand_values),
+        toml::Value::Table(table) => table.iter_mut().for_each(|(_, value)| expand_values(value)),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{BufReader, Error, ErrorKind};
@@ -158,7 +174,9 @@ mod tests {
             let mut pkg = PackageData::new("test-package");
             pkg.metadata_mut().set_license(LicenseType::None);
             pkg.metadata_mut().set_maintainers(&["AdmiringWorm"]);
-            pkg.metadata_mut().set_project_url("https://test.com");
+            pkg.metadata_mut()
+                .set_project_url("https://test.com")
+                .unwrap();
             pkg.metadata_mut().summary =
                 "Some kind of summary (or description in some packages)".to_owned();
             pkg
@@ -240,7 +258,8 @@ mod tests {
             pkg.metadata_mut()
                 .set_license(LicenseType::Expression("MIT".to_owned()));
             pkg.metadata_mut()
-                .set_project_url("https:/_Software_Location_REMOVE_OR_FILL_OUT_");
+                .set_project_url("https:/_Software_Location_REMOVE_OR_FILL_OUT_")
+                .unwrap();
             pkg
         };
         expected.metadata_mut().set_chocolatey({
@@ -269,7 +288,9 @@ mod tests {
                 .unwrap(),
             });
             metadata.set_maintainers(&["AdmiringWorm", "yying"]);
-            metadata.set_project_url("http://astyle.sourceforge.net/");
+            metadata
+                .set_project_url("http://astyle.sourceforge.net/")
+                .unwrap();
             metadata.summary = "Artistic Style is a source code indenter, formater, and beutifier \
                                 for the C, C++, C++/CLI, Objective-C, C# and Java programming \
                                 languages."
@@ -316,4 +337,84 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn read_data_should_round_trip_metadata_and_updater_data() {
+        let parser = TomlParser;
+        let expected = {
+            let mut pkg = PackageData::new("test-package");
+            pkg.metadata_mut()
+                .set_license(LicenseType::Expression("MIT".to_owned()));
+            pkg.metadata_mut()
+                .set_project_url("https://test.com")
+                .unwrap();
+            pkg.updater_mut().set_chocolatey({
+                let mut choco = ChocolateyUpdaterData::new();
+                choco.updater_type = ChocolateyUpdaterType::Installer;
+                choco.parse_url = Some(ChocolateyParseUrl::UrlWithRegex {
+                    url: Url::parse("https://test.com/releases").unwrap(),
+                    regex: r"test-(?P<version>[\d\.]+)\.exe$".into(),
+                });
+                choco.add_regex("arch32", r"test-[\d\.]+\.exe$");
+                choco
+            });
+            pkg
+        };
+        let text = toml::to_string(&expected).unwrap();
+
+        let actual = parser.read_data(&mut text.as_bytes()).unwrap();
+
+        assert!(text.contains("[metadata]"));
+        assert!(text.contains("[updater.chocolatey]"));
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn read_data_should_expand_environment_variables() {
+        std::env::set_var("AER_TOML_TEST_PROJECT", "expanded-project");
+        std::env::remove_var("AER_TOML_TEST_UNSET");
+        let text = r#"
+[metadata]
+id = "test-package"
+project_url = "https://test.com/${AER_TOML_TEST_PROJECT}"
+summary = "Summary of ${AER_TOML_TEST_PROJECT} with ${AER_TOML_TEST_UNSET}"
+maintainers = ["AdmiringWorm"]
+"#;
+        let parser = TomlParser;
+
+        let actual = parser.read_data(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(
+            actual.metadata().project_url().as_str(),
+            "https://test.com/expanded-project"
+        );
+        assert_eq!(
+            actual.metadata().summary,
+            "Summary of expanded-project with ${AER_TOML_TEST_UNSET}"
+        );
+    }
+
+    #[test]
+    fn read_data_should_keep_escaped_variables_in_regexes() {
+        std::env::set_var("AER_TOML_TEST_PROJECT", "expanded-project");
+        let text = r#"
+[metadata]
+id = "test-package"
+maintainers = ["AdmiringWorm"]
+
+[updater.chocolatey.regexes]
+escaped = '''/(?P<version>[\d\.]+)/$${AER_TOML_TEST_PROJECT}\.zip$'''
+repetition = '''^v\d${1,3}$'''
+"#;
+        let parser = TomlParser;
+
+        let actual = parser.read_data(&mut text.as_bytes()).unwrap();
+        let updater = actual.updater().chocolatey();
+
+        assert_eq!(
+            updater.regexes()["escaped"],
+            r"/(?P<version>[\d\.]+)/${AER_TOML_TEST_PROJECT}\.zip$"
+        );
+        assert_eq!(updater.regexes()["repetition"], r"^v\d${1,3}$");
+    }
 }
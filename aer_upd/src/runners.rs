@@ -149,7 +149,11 @@ impl RunnerCombiner for aer_data::PackageData {
         for (key, val) in data.data {
             match val {
                 RunnerChildType::Data(val) => match key.trim() {
-                    "project_url" => self.metadata_mut().set_project_url(&val),
+                    "project_url" => {
+                        if let Err(err) = self.metadata_mut().set_project_url(&val) {
+                            log::warn!("Unable to set project url '{}': {}", val, err);
+                        }
+                    }
                     "summary" => self.metadata_mut().summary = val,
                     _ => {}
                 },
@@ -59,6 +59,12 @@ impl Versions {
         }
     }
 
+    /// Converts the version to a chocolatey compatible version.
+    ///
+    /// This conversion is specific to Chocolatey and is lossy, as pre-release
+    /// labels are reformatted (*ie: `-alpha.66` becomes `-alpha0066`*) and any
+    /// build metadata is dropped. Use [Versions::to_semver_string] when the
+    /// original semantic version form must be kept.
     #[cfg(feature = "chocolatey")]
     #[cfg_attr(docsrs, doc(cfg(feature = "chocolatey")))]
     pub fn to_choco(&self) -> chocolatey::ChocoVersion {
@@ -76,6 +82,114 @@ impl Versions {
             Versions::Choco(ver) => SemVersion::from(ver.clone()),
         }
     }
+
+    /// Returns the version formatted as a semantic version, keeping the
+    /// original form of pre-release labels and build metadata.
+    ///
+    /// Parsing the returned string of a semantic version will always result in
+    /// an equal [Versions] item.
+    pub fn to_semver_string(&self) -> String {
+        match self {
+            Versions::SemVer(semver) => semver.to_string(),
+            #[cfg(feature = "chocolatey")]
+            Versions::Choco(_) => self.to_semver().to_string(),
+        }
+    }
+
+    /// Checks if the two versions are equal, without taking any build metadata
+    /// (*ie: `+build.5`*) into account.
+    ///
+    /// The fourth part of a chocolatey version is not treated as build
+    /// metadata, and will still be compared.
+    pub fn eq_ignoring_build(&self, other: &Versions) -> bool {
+        match (self, other) {
+            (Versions::SemVer(left), Versions::SemVer(right)) => {
+                left.major == right.major
+                    && left.minor == right.minor
+                    && left.patch == right.patch
+                    && left.pre == right.pre
+            }
+            #[cfg(feature = "chocolatey")]
+            _ => self.to_choco() == other.to_choco(),
+        }
+    }
+
+    /// Returns a new version with the major part incremented, and the
+    /// remaining parts reset to `0`. Any pre-release and build part is
+    /// removed.
+    pub fn bump_major(&self) -> Versions {
+        match self {
+            Versions::SemVer(semver) => {
+                let mut semver = semver.clone();
+                semver.increment_major();
+                Versions::SemVer(semver)
+            }
+            #[cfg(feature = "chocolatey")]
+            Versions::Choco(ver) => Versions::Choco(ver.bump_major()),
+        }
+    }
+
+    /// Returns a new version with the minor part incremented, and the patch
+    /// part reset to `0`. Any pre-release and build part is removed.
+    pub fn bump_minor(&self) -> Versions {
+        match self {
+            Versions::SemVer(semver) => {
+                let mut semver = semver.clone();
+                semver.increment_minor();
+                Versions::SemVer(semver)
+            }
+            #[cfg(feature = "chocolatey")]
+            Versions::Choco(ver) => Versions::Choco(ver.bump_minor()),
+        }
+    }
+
+    /// Returns a new version with the patch part incremented. Any pre-release
+    /// and build part is removed.
+    pub fn bump_patch(&self) -> Versions {
+        match self {
+            Versions::SemVer(semver) => {
+                let mut semver = semver.clone();
+                semver.increment_patch();
+                Versions::SemVer(semver)
+            }
+            #[cfg(feature = "chocolatey")]
+            Versions::Choco(ver) => Versions::Choco(ver.bump_patch()),
+        }
+    }
+
+    /// Returns a new chocolatey version with the fourth part of the version
+    /// incremented, keeping any pre-release part. This is used when the
+    /// package needs to be updated without any upstream changes (*ie: fixing
+    /// the install script*).
+    ///
+    /// Semantic versions do not have a fourth part, and will be converted to a
+    /// chocolatey version (see [Versions::to_choco]).
+    #[cfg(feature = "chocolatey")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "chocolatey")))]
+    pub fn bump_revision(&self) -> Versions {
+        Versions::Choco(self.to_choco().bump_revision())
+    }
+}
+
+impl PartialOrd for Versions {
+    /// Compares the two versions, semantic versions are compared using the
+    /// semantic version rules, while chocolatey versions (or a mix of both) are
+    /// compared as chocolatey versions.
+    ///
+    /// When a semantic version and a chocolatey version are considered to be
+    /// the same version, `None` is returned as they are not treated as equal.
+    fn partial_cmp(&self, other: &Versions) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Versions::SemVer(left), Versions::SemVer(right)) => left.partial_cmp(right),
+            #[cfg(feature = "chocolatey")]
+            (Versions::Choco(left), Versions::Choco(right)) => left.partial_cmp(right),
+            #[cfg(feature = "chocolatey")]
+            _ => match self.to_choco().cmp(&other.to_choco()) {
+                std::cmp::Ordering::Equal => None,
+                ordering => Some(ordering),
+            },
+        }
+    }
 }
 
 impl Display for Versions {
@@ -207,4 +321,144 @@ mod tests {
 
         assert_eq!(version.to_string(), expected);
     }
+
+    #[rstest]
+    #[case("4.2.1+5", "4.2.1+6")]
+    #[case("4.2.1-alpha.66+99", "4.2.1-alpha.66")]
+    #[case("4.2.1", "4.2.1+build.5")]
+    #[cfg_attr(feature = "chocolatey", case("5.2.1.6", "5.2.1.6"))]
+    fn eq_ignoring_build_should_be_equal_on_build_differences(
+        #[case] left: &str,
+        #[case] right: &str,
+    ) {
+        let left = Versions::parse(left).unwrap();
+        let right = Versions::parse(right).unwrap();
+
+        assert!(left.eq_ignoring_build(&right));
+        assert!(right.eq_ignoring_build(&left));
+    }
+
+    #[rstest]
+    #[case("4.2.1-alpha.5+5", "4.2.1-alpha.6+5")]
+    #[case("4.2.1-alpha.5", "4.2.1")]
+    #[case("4.2.1+5", "4.2.2+5")]
+    #[cfg_attr(feature = "chocolatey", case("5.2.1.6", "5.2.1.7"))]
+    #[cfg_attr(feature = "chocolatey", case("5.2.1-alpha.66", "5.2.1-beta0066"))]
+    fn eq_ignoring_build_should_not_be_equal_on_other_differences(
+        #[case] left: &str,
+        #[case] right: &str,
+    ) {
+        let left = Versions::parse(left).unwrap();
+        let right = Versions::parse(right).unwrap();
+
+        assert!(!left.eq_ignoring_build(&right));
+        assert!(!right.eq_ignoring_build(&left));
+    }
+
+    #[rstest]
+    #[case("0.0.0")]
+    #[case("1.0.0")]
+    #[case("4.2.1")]
+    #[case("4.2.1-alpha")]
+    #[case("4.2.1-alpha.66")]
+    #[case("4.2.1-alpha.5+6")]
+    #[case("4.2.1-rc.1.beta.2")]
+    #[case("4.2.1+build.5")]
+    #[case("10.20.30-0.3.7")]
+    #[case("1.0.0-x.7.z.92")]
+    fn to_semver_string_should_round_trip_semantic_versions(#[case] test: &str) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.to_semver_string();
+
+        assert_eq!(actual, test);
+        assert_eq!(Versions::parse(&actual).unwrap(), version);
+    }
+
+    #[rstest]
+    #[cfg(feature = "chocolatey")]
+    #[case("5.2-alpha.5", "5.2.0-alpha.5")]
+    #[case("4.2.1-alpha54.2", "4.2.1-alpha-54.2")]
+    fn to_semver_string_should_convert_chocolatey_versions(
+        #[case] test: &str,
+        #[case] expected: &str,
+    ) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.to_semver_string();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case("1.0.0", "2.0.0")]
+    #[case("1.0.0-alpha.1", "1.0.0")]
+    #[case("1.0.0-alpha.1", "1.0.0-alpha.2")]
+    #[cfg_attr(feature = "chocolatey", case("1.0.0.1", "1.0.0.2"))]
+    #[cfg_attr(feature = "chocolatey", case("1.0.0", "1.0.0.1"))]
+    #[cfg_attr(feature = "chocolatey", case("1.0", "1.0.1"))]
+    fn partial_cmp_should_order_versions(#[case] lower: &str, #[case] higher: &str) {
+        let lower = Versions::parse(lower).unwrap();
+        let higher = Versions::parse(higher).unwrap();
+
+        assert!(lower < higher);
+        assert!(higher > lower);
+    }
+
+    #[rstest]
+    #[case("1.2.3", "2.0.0")]
+    #[case("1.2.3-beta.1+build.5", "2.0.0")]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4", "2.0.0"))]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4-beta", "2.0.0"))]
+    fn bump_major_should_increment_major_version(#[case] test: &str, #[case] expected: &str) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.bump_major();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.3.0")]
+    #[case("1.2.3-beta.1+build.5", "1.3.0")]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4", "1.3.0"))]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4-beta", "1.3.0"))]
+    fn bump_minor_should_increment_minor_version(#[case] test: &str, #[case] expected: &str) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.bump_minor();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.4")]
+    #[case("1.2.3-beta.1+build.5", "1.2.4")]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4", "1.2.4"))]
+    #[cfg_attr(feature = "chocolatey", case("1.2.3.4-beta", "1.2.4"))]
+    fn bump_patch_should_increment_patch_version(#[case] test: &str, #[case] expected: &str) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.bump_patch();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("1.2.3", "1.2.3.1")]
+    #[case("1.2.3-beta", "1.2.3.1-beta")]
+    #[case("1.2.3.4", "1.2.3.5")]
+    #[case("1.2.3.4-beta", "1.2.3.5-beta")]
+    #[cfg(feature = "chocolatey")]
+    fn bump_revision_should_increment_fourth_part(#[case] test: &str, #[case] expected: &str) {
+        let version = Versions::parse(test).unwrap();
+
+        let actual = version.bump_revision();
+
+        assert_eq!(
+            actual,
+            Versions::Choco(chocolatey::ChocoVersion::parse(expected).unwrap())
+        );
+        assert_eq!(actual.to_string(), expected);
+    }
 }
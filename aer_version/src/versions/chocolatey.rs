@@ -173,6 +173,47 @@ impl ChocoVersion {
         self.set_prerelease(pre);
         self
     }
+
+    /// Returns a new version with the major part incremented, and the
+    /// remaining parts reset to `0`. The pre-release and build part is
+    /// removed.
+    pub fn bump_major(&self) -> ChocoVersion {
+        let mut choco = ChocoVersion::new(self.major.saturating_add(1), 0);
+        if self.patch.is_some() {
+            choco.set_patch(0);
+        }
+
+        choco
+    }
+
+    /// Returns a new version with the minor part incremented, and the patch
+    /// part reset to `0`. The pre-release and build part is removed.
+    pub fn bump_minor(&self) -> ChocoVersion {
+        let mut choco = ChocoVersion::new(self.major, self.minor.saturating_add(1));
+        if self.patch.is_some() {
+            choco.set_patch(0);
+        }
+
+        choco
+    }
+
+    /// Returns a new version with the patch part incremented. The pre-release
+    /// and build part is removed.
+    pub fn bump_patch(&self) -> ChocoVersion {
+        let patch = self.patch.unwrap_or(0).saturating_add(1);
+
+        ChocoVersion::with_patch(self.major, self.minor, patch)
+    }
+
+    /// Returns a new version with the build part (fourth part of the version)
+    /// incremented. This is used when the package itself needs to be updated
+    /// without any upstream changes, as such the pre-release part is kept.
+    pub fn bump_revision(&self) -> ChocoVersion {
+        let mut choco = self.clone();
+        choco.set_build(self.build.unwrap_or(0).saturating_add(1));
+
+        choco
+    }
 }
 
 impl Ord for ChocoVersion {
@@ -513,6 +554,70 @@ mod tests {
 
     use super::*;
 
+    #[rstest(
+        test,
+        expected,
+        case("1.2", "2.0"),
+        case("1.2.3", "2.0.0"),
+        case("1.2.3.4", "2.0.0"),
+        case("1.2.3-beta", "2.0.0")
+    )]
+    fn bump_major_should_increment_major_and_reset_other_parts(test: &str, expected: &str) {
+        let version = ChocoVersion::parse(test).unwrap();
+
+        let actual = version.bump_major();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest(
+        test,
+        expected,
+        case("1.2", "1.3"),
+        case("1.2.3", "1.3.0"),
+        case("1.2.3.4", "1.3.0"),
+        case("1.2.3-beta", "1.3.0")
+    )]
+    fn bump_minor_should_increment_minor_and_reset_patch(test: &str, expected: &str) {
+        let version = ChocoVersion::parse(test).unwrap();
+
+        let actual = version.bump_minor();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest(
+        test,
+        expected,
+        case("1.2", "1.2.1"),
+        case("1.2.3", "1.2.4"),
+        case("1.2.3.4", "1.2.4"),
+        case("1.2.3-beta", "1.2.4")
+    )]
+    fn bump_patch_should_increment_patch(test: &str, expected: &str) {
+        let version = ChocoVersion::parse(test).unwrap();
+
+        let actual = version.bump_patch();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
+    #[rstest(
+        test,
+        expected,
+        case("1.2", "1.2.0.1"),
+        case("1.2.3", "1.2.3.1"),
+        case("1.2.3.4", "1.2.3.5"),
+        case("1.2.3-beta", "1.2.3.1-beta")
+    )]
+    fn bump_revision_should_increment_build_and_keep_pre_release(test: &str, expected: &str) {
+        let version = ChocoVersion::parse(test).unwrap();
+
+        let actual = version.bump_revision();
+
+        assert_eq!(actual.to_string(), expected);
+    }
+
     #[test]
     fn display_should_output_major_and_minor_version() {
         let version = ChocoVersion::new(1, 2);
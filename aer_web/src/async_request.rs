@@ -0,0 +1,628 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Section responsible for allowing requests to be sent to remote locations
+//! from within an asynchronous runtime. The requests and responses mirror the
+//! blocking [WebRequest](crate::WebRequest), and are parsed the same way.
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use reqwest::header::HeaderMap;
+use reqwest::{header, Client, RequestBuilder, Response, StatusCode, Url};
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+
+use crate::errors::WebError;
+use crate::request::{
+    check_status_code, classify_binary_response, default_headers, BinaryResponseKind,
+    ConditionalRequest, Credentials, ACCEPTED_TYPES, APP_USER_AGENT,
+};
+use crate::response::{
+    check_partial_file, compile_regex, decode_body, finish_download, get_from_disposition,
+    get_from_url, parent_link_element, parse_html, ResponseType,
+};
+use crate::{LinkElement, LinkTypeMapping};
+
+/// Holds the necessary information to create asynchronous requests to
+/// websites. Sends the same headers as the blocking
+/// [WebRequest](crate::WebRequest).
+///
+/// ## Examples
+///
+/// Aquiring the links from an html page.
+/// ```no_run
+/// use aer_web::AsyncWebRequest;
+///
+/// # async fn run() {
+/// let request = AsyncWebRequest::create();
+/// let response = request
+///     .get_html_response("https://httpbin.org/links/5/2")
+///     .await
+///     .unwrap();
+/// let (parent_link, links) = response.read(None).await.unwrap();
+/// # }
+/// ```
+pub struct AsyncWebRequest {
+    client: Client,
+    credentials: Option<Credentials>,
+}
+
+impl AsyncWebRequest {
+    /// Creates a new instance of an asynchronous web request, the client is
+    /// configured the same way as the blocking
+    /// [create](crate::WebRequest::create) function.
+    pub fn create() -> AsyncWebRequest {
+        let mut client = Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .default_headers(default_headers())
+            .gzip(true);
+        if cfg!(windows) {
+            client = client.use_rustls_tls();
+        }
+
+        AsyncWebRequest {
+            client: client.build().unwrap(),
+            credentials: None,
+        }
+    }
+
+    /// Sets the user and password that will be sent using basic
+    /// authentication with every request, replacing any previously set
+    /// credentials.
+    pub fn basic_auth(self, user: &str, password: Option<&str>) -> AsyncWebRequest {
+        AsyncWebRequest {
+            credentials: Some(Credentials::Basic {
+                user: user.into(),
+                password: password.map(Into::into),
+            }),
+            ..self
+        }
+    }
+
+    /// Sets the token that will be sent as a bearer token with every request,
+    /// replacing any previously set credentials.
+    pub fn bearer_token(self, token: &str) -> AsyncWebRequest {
+        AsyncWebRequest {
+            credentials: Some(Credentials::Bearer(token.into())),
+            ..self
+        }
+    }
+
+    /// Makes a request to a website and requesting the html at the location,
+    /// the same as [get_html_response](crate::WebRequest::get_html_response).
+    ///
+    /// The links in the response can be found by calling the
+    /// [read](AsyncHtmlResponse::read) function.
+    pub async fn get_html_response(&self, url: &str) -> Result<AsyncHtmlResponse, WebError> {
+        let url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+
+        let response = self
+            .get(url)
+            .header(header::ACCEPT, ACCEPTED_TYPES["html"])
+            .send()
+            .await
+            .map_err(WebError::Request)?;
+
+        Ok(AsyncHtmlResponse::new(check_status(response)?))
+    }
+
+    /// Makes a request to a web endpoint and requests a result in the type of
+    /// a binary without downloading the actual upstream content, the same as
+    /// [get_binary_response](crate::WebRequest::get_binary_response).
+    pub async fn get_binary_response(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<ResponseType<AsyncBinaryResponse>, WebError> {
+        self.get_conditional_binary_response(url, &ConditionalRequest::new(etag, last_modified))
+            .await
+    }
+
+    /// Makes a request to a web endpoint the same way as
+    /// [get_conditional_binary_response](crate::WebRequest::get_conditional_binary_response),
+    /// including resuming a partial file.
+    pub async fn get_conditional_binary_response(
+        &self,
+        url: &str,
+        conditional: &ConditionalRequest,
+    ) -> Result<ResponseType<AsyncBinaryResponse>, WebError> {
+        let parsed_url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+        let full_request = ConditionalRequest::default();
+        let mut conditional = conditional;
+
+        loop {
+            let mut request = self
+                .get(parsed_url.clone())
+                .headers(conditional.binary_headers()?);
+            let range = conditional.range_headers()?;
+            if let Some((_, range_headers)) = &range {
+                request = request.headers(range_headers.clone());
+            }
+            let response = request.send().await.map_err(WebError::Request)?;
+            let status = response.status();
+
+            let range_start =
+                match classify_binary_response(status, response.headers(), range.map(|r| r.0)) {
+                    BinaryResponseKind::Updated => {
+                        return Ok(ResponseType::Updated(status.as_u16()))
+                    }
+                    BinaryResponseKind::Restart => {
+                        conditional = &full_request;
+                        continue;
+                    }
+                    BinaryResponseKind::Resume(start) => Some(start),
+                    BinaryResponseKind::Full => None,
+                };
+
+            let response = check_status(response)?;
+
+            return Ok(ResponseType::New(
+                AsyncBinaryResponse::new(response, parsed_url).with_range_start(range_start),
+                status.as_u16(),
+            ));
+        }
+    }
+
+    fn get(&self, url: Url) -> RequestBuilder {
+        let request = self.client.get(url);
+
+        match &self.credentials {
+            Some(Credentials::Basic { user, password }) => {
+                request.basic_auth(user, password.as_ref())
+            }
+            Some(Credentials::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Holds a single asynchronous html response, the same as the blocking
+/// [HtmlResponse](crate::response::HtmlResponse).
+#[derive(Debug)]
+pub struct AsyncHtmlResponse {
+    response: Response,
+    link_types: LinkTypeMapping,
+    strip_v_prefix: bool,
+}
+
+impl AsyncHtmlResponse {
+    /// Creates a new instance of the [AsyncHtmlResponse] structure to hold the
+    /// current response, and allow reading the content from that response.
+    pub fn new(response: Response) -> AsyncHtmlResponse {
+        AsyncHtmlResponse {
+            response,
+            link_types: LinkTypeMapping::default(),
+            strip_v_prefix: true,
+        }
+    }
+
+    /// Sets the mapping that will be used to classify the type of the links
+    /// found when reading the response. Uses the default
+    /// [LinkTypeMapping] if not set.
+    pub fn set_link_types(&mut self, link_types: LinkTypeMapping) {
+        self.link_types = link_types;
+    }
+
+    /// Sets whether a leading `v` or `V` (*ie: `v1.2.3`*) should be removed
+    /// from any captured version before it is parsed. This is enabled by
+    /// default.
+    pub fn set_strip_v_prefix(&mut self, strip_v_prefix: bool) {
+        self.strip_v_prefix = strip_v_prefix;
+    }
+
+    /// Returns all of the headers that was returned by the web server.
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// Returns the status that was returned with the rest of the response.
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    /// Reads the current response, and extracts any link elements that were
+    /// found in the body as well as the link that were used to get the
+    /// response itself (*the same as
+    /// [HtmlResponse::read](crate::response::HtmlResponse::read)*).
+    pub async fn read(self, re: Option<&str>) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+        self.read_html(re, None).await
+    }
+
+    /// Reads the current response the same way as
+    /// [read](AsyncHtmlResponse::read), but additionally extracts a version
+    /// from the text of the html page using the specified `version_re` regex
+    /// (*the same as
+    /// [HtmlResponse::read_with_version](crate::response::HtmlResponse::read_with_version)*).
+    pub async fn read_with_version(
+        self,
+        re: Option<&str>,
+        version_re: &str,
+    ) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+        let version_re = compile_regex(version_re)?;
+
+        self.read_html(re, Some(&version_re)).await
+    }
+
+    async fn read_html(
+        self,
+        re: Option<&str>,
+        version_re: Option<&regex::Regex>,
+    ) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+        let content_type = self
+            .response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_owned());
+        let parent_link = parent_link_element(
+            self.response.url(),
+            content_type.as_deref().unwrap_or("UNKNOWN"),
+        );
+
+        let bytes = self.response.bytes().await.map_err(WebError::Request)?;
+        let body = decode_body(&bytes, content_type.as_deref());
+
+        parse_html(
+            body,
+            parent_link,
+            re,
+            version_re,
+            &self.link_types,
+            self.strip_v_prefix,
+        )
+    }
+}
+
+/// Holds a single asynchronous binary response, the same as the blocking
+/// [BinaryResponse](crate::response::BinaryResponse).
+#[derive(Debug)]
+pub struct AsyncBinaryResponse {
+    response: Response,
+    url: Url,
+    work_dir: PathBuf,
+    range_start: Option<u64>,
+}
+
+impl PartialEq for AsyncBinaryResponse {
+    fn eq(&self, rhs: &AsyncBinaryResponse) -> bool {
+        self.work_dir == rhs.work_dir
+    }
+}
+
+impl AsyncBinaryResponse {
+    /// Creates a new instance of the [AsyncBinaryResponse] structure to hold
+    /// the current response, and allow downloading the remote file from the
+    /// content response.
+    pub fn new(response: Response, url: Url) -> AsyncBinaryResponse {
+        AsyncBinaryResponse {
+            response,
+            url,
+            work_dir: PathBuf::new(),
+            range_start: None,
+        }
+    }
+
+    /// Sets the position the partial content of the response starts at, the
+    /// content will then be appended to the existing partial file.
+    pub(crate) fn with_range_start(mut self, range_start: Option<u64>) -> AsyncBinaryResponse {
+        self.range_start = range_start;
+        self
+    }
+
+    /// Sets the current work directory (the directory where files will be
+    /// downloaded), the same as
+    /// [BinaryResponse::set_work_dir](crate::response::BinaryResponse::set_work_dir).
+    pub fn set_work_dir(&mut self, path: &Path) {
+        self.work_dir = PathBuf::from(path);
+    }
+
+    /// Tries to get the name of the remote file by either reading the
+    /// disposition header, or checking the url if it contains an extension.
+    pub fn file_name(&self) -> Option<String> {
+        get_from_disposition(self.response.headers()).or_else(|| get_from_url(self.response.url()))
+    }
+
+    /// Returns all of the headers that was returned by the web server, these
+    /// can be used to create the conditional request of the next download
+    /// (see [ConditionalRequest::from_headers]).
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// Returns the status that was returned with the rest of the response.
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    /// Reads and downloads the response content, the same as
+    /// [BinaryResponse::read](crate::response::BinaryResponse::read).
+    ///
+    /// ## Arguments
+    ///
+    /// - `output`: The name of the file to create, if not specified it will be
+    ///   resolved from the response.
+    ///
+    /// ## Returns
+    ///
+    /// On a successful download, the written path will be returned.
+    pub async fn read(self, output: Option<&str>) -> Result<PathBuf, WebError> {
+        let output = if let Some(output) = output {
+            output.into()
+        } else {
+            self.file_name()
+                .ok_or_else(|| WebError::Other("Unable to extract file name request".into()))?
+        };
+        let output = self.work_dir.join(output);
+
+        match self.range_start {
+            Some(start) => {
+                check_partial_file(&output, start)?;
+                download(self.response, &self.url, &output, true).await
+            }
+            None => download(self.response, &self.url, &output, false).await,
+        }
+    }
+}
+
+/// Implements functions that only makes sense to be called when the response
+/// type is an asynchronous binary response.
+impl ResponseType<AsyncBinaryResponse> {
+    /// Sets the directory that should be used when calling the child response.
+    /// This function should not panic even if the response is considered up to
+    /// date.
+    pub fn set_work_dir(&mut self, path: &Path) {
+        if let ResponseType::New(item, _) = self {
+            item.set_work_dir(path)
+        }
+    }
+}
+
+fn check_status(response: Response) -> Result<Response, WebError> {
+    check_status_code(response.status(), response.url())?;
+    info!(
+        "The web server responded with status: {}!",
+        response.status()
+    );
+
+    Ok(response)
+}
+
+async fn download(
+    mut response: Response,
+    url: &Url,
+    output: &Path,
+    append: bool,
+) -> Result<PathBuf, WebError> {
+    info!("Downloading '{}' to '{}'", url, output.display());
+
+    let expected = response.content_length();
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .append(append)
+        .truncate(!append)
+        .open(output)
+        .await?;
+    let mut writer = BufWriter::new(file);
+    let mut written = 0;
+
+    let result = loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if let Err(err) = writer.write_all(&chunk).await {
+                    break Err(WebError::IoError(err));
+                }
+                written += chunk.len() as u64;
+            }
+            Ok(None) => break writer.flush().await.map_err(WebError::IoError),
+            Err(err) => break Err(WebError::Request(err)),
+        }
+    };
+    drop(writer);
+
+    finish_download(url, output, result, expected, written, append)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_server, LinkType};
+
+    #[tokio::test]
+    async fn get_html_response_should_get_links_from_page() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[("Content-Type", "text/html; charset=windows-1252")],
+                b"<html><body><a href=\"/downloads/tool-1.2.0.zip\">T\xe9l\xe9charger</a>\
+                  <a href=\"\">Empty</a></body></html>",
+            )
+        });
+        let request = AsyncWebRequest::create();
+
+        let response = request
+            .get_html_response(&format!("{}/releases", url))
+            .await
+            .unwrap();
+        let (parent, links) = response.read(None).await.unwrap();
+
+        assert_eq!(parent.link.as_str(), format!("{}/releases", url));
+        assert_eq!(parent.link_type, LinkType::Html);
+        assert_eq!(links.len(), 1);
+        assert_eq!(
+            links[0].link.as_str(),
+            format!("{}/downloads/tool-1.2.0.zip", url)
+        );
+        assert_eq!(links[0].text, "T\u{e9}l\u{e9}charger");
+    }
+
+    #[tokio::test]
+    async fn read_should_keep_v_prefix_when_not_stripping() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[("Content-Type", "text/html")],
+                b"<html><body><a href=\"/download/v1.2.3/setup.exe\">Download</a></body></html>",
+            )
+        });
+        let request = AsyncWebRequest::create();
+        let mut response = request.get_html_response(&url).await.unwrap();
+        response.set_strip_v_prefix(false);
+
+        let (_, links) = response
+            .read(Some(r"/download/(?P<version>[vV]?[\d\.]+)/"))
+            .await
+            .unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].version, None);
+    }
+
+    #[tokio::test]
+    async fn get_html_response_should_return_error_on_failure_status() {
+        let url = test_server::serve(|_| test_server::response(404, &[], b""));
+        let request = AsyncWebRequest::create();
+
+        let err = request.get_html_response(&url).await.unwrap_err();
+
+        assert!(matches!(err, WebError::HttpStatus { code: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn get_html_response_should_send_credentials() {
+        let url = test_server::serve(|request| {
+            if request.header("authorization") == Some("Bearer secret-token") {
+                test_server::response(200, &[("Content-Type", "text/html")], b"<html></html>")
+            } else {
+                test_server::response(401, &[], b"")
+            }
+        });
+
+        let response = AsyncWebRequest::create()
+            .bearer_token("secret-token")
+            .get_html_response(&url)
+            .await;
+        let err = AsyncWebRequest::create()
+            .get_html_response(&url)
+            .await
+            .unwrap_err();
+
+        assert!(response.is_ok());
+        assert!(matches!(err, WebError::HttpStatus { code: 401, .. }));
+    }
+
+    #[tokio::test]
+    async fn get_binary_response_should_download_file() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[
+                    ("Content-Type", "application/octet-stream"),
+                    (
+                        "Content-Disposition",
+                        "attachment; filename=\"async-test.txt\"",
+                    ),
+                ],
+                b"Hello from async",
+            )
+        });
+        let work_dir = std::env::temp_dir().join("aer_web_async_download");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let request = AsyncWebRequest::create();
+
+        let mut response = match request
+            .get_binary_response(&format!("{}/download", url), None, None)
+            .await
+            .unwrap()
+        {
+            ResponseType::New(response, 200) => response,
+            _ => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&work_dir);
+        let path = response.read(None).await.unwrap();
+
+        assert_eq!(path, work_dir.join("async-test.txt"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"Hello from async");
+        std::fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_binary_response_should_return_updated_response_by_etag() {
+        let url = test_server::serve(|request| {
+            if request.header("if-none-match") == Some("\"abc\"") {
+                test_server::response(304, &[], b"")
+            } else {
+                test_server::response(200, &[("ETag", "\"abc\"")], b"content")
+            }
+        });
+        let request = AsyncWebRequest::create();
+
+        let response = request
+            .get_binary_response(&format!("{}/test.bin", url), Some("abc"), None)
+            .await
+            .unwrap();
+
+        assert_eq!(response, ResponseType::Updated(304));
+    }
+
+    #[tokio::test]
+    async fn get_conditional_binary_response_should_resume_partial_file() {
+        let url = test_server::serve(|request| {
+            const CONTENT: &[u8] = b"0123456789abcdef";
+            match (request.header("range"), request.header("if-range")) {
+                (Some("bytes=6-"), Some("\"v1\"")) => {
+                    test_server::response(206, &[("Content-Range", "bytes 6-15/16")], &CONTENT[6..])
+                }
+                _ => test_server::response(200, &[("ETag", "\"v1\"")], CONTENT),
+            }
+        });
+        let path = std::env::temp_dir().join("async-resume-partial-test.bin");
+        std::fs::write(&path, b"012345").unwrap();
+        let request = AsyncWebRequest::create();
+        let conditional = ConditionalRequest::new(Some("v1"), None).with_partial_file(&path);
+
+        let mut response = request
+            .get_conditional_binary_response(
+                &format!("{}/async-resume-partial-test.bin", url),
+                &conditional,
+            )
+            .await
+            .unwrap();
+        response.set_work_dir(path.parent().unwrap());
+        let result = match response {
+            ResponseType::New(response, 206) => response.read(None).await.unwrap(),
+            _ => panic!("Expected a partial response!"),
+        };
+
+        assert_eq!(result, path);
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn get_binary_response_should_return_error_on_truncated_download() {
+        let url = test_server::serve(|_| test_server::truncated_response(b"Hello", 10));
+        let work_dir = std::env::temp_dir().join("aer_web_async_truncated");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let request = AsyncWebRequest::create();
+
+        let mut response = request.get_binary_response(&url, None, None).await.unwrap();
+        response.set_work_dir(&work_dir);
+        let err = match response {
+            ResponseType::New(response, _) => {
+                response.read(Some("truncated.txt")).await.unwrap_err()
+            }
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+
+        assert!(!work_dir.join("truncated.txt").exists());
+        std::fs::remove_dir_all(&work_dir).unwrap();
+        match err {
+            WebError::Request(err) => assert!(err.is_body()),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+}
@@ -0,0 +1,426 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Persistent cache of downloaded files, allowing files that have not changed
+//! on the server to be reused across multiple runs.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::WebError;
+use crate::request::WebRequest;
+use crate::response::{ResponseType, WebResponse};
+
+/// The name of the file that the cache index is stored in.
+const INDEX_FILE: &str = "index.json";
+
+/// Information about a single file that has been downloaded and stored in the
+/// cache.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CacheEntry {
+    /// The etag that was returned by the server when the file was downloaded.
+    pub etag: Option<String>,
+    /// The last modified date that was returned by the server when the file
+    /// was downloaded.
+    pub last_modified: Option<String>,
+    /// The location of the downloaded file.
+    pub path: PathBuf,
+}
+
+/// A cache of downloaded files, the cache is stored in a directory together
+/// with a small `JSON` index of the url, etag and last modified date of every
+/// downloaded file.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use aer_web::{DownloadCache, WebRequest};
+///
+/// let request = WebRequest::create();
+/// let mut cache = DownloadCache::open(&std::env::temp_dir().join("aer-cache")).unwrap();
+/// let path = cache
+///     .download(&request, "https://example.org/test.zip")
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct DownloadCache {
+    dir: PathBuf,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl DownloadCache {
+    /// Opens the cache stored in the specified directory, creating the
+    /// directory if it do not already exist.
+    pub fn open(dir: &Path) -> Result<DownloadCache, WebError> {
+        std::fs::create_dir_all(dir)?;
+        let index = dir.join(INDEX_FILE);
+
+        let entries = if index.is_file() {
+            let content = std::fs::read_to_string(&index)?;
+            serde_json::from_str(&content).map_err(WebError::CacheIndex)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(DownloadCache {
+            dir: PathBuf::from(dir),
+            entries,
+        })
+    }
+
+    /// Returns the directory where the cache is stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached entry for the specified url, or `None` if the url
+    /// have not been cached or the cached file no longer exist.
+    pub fn get(&self, url: &str) -> Option<&CacheEntry> {
+        self.entries.get(url).filter(|entry| entry.path.is_file())
+    }
+
+    /// Adds or replaces the cached entry for the specified url.
+    ///
+    /// ## Notes
+    ///
+    /// - The index is not written until [save](DownloadCache::save) is called.
+    /// - When the replaced entry pointed to a different file in the cache
+    ///   directory, the old file is removed.
+    pub fn insert(&mut self, url: &str, entry: CacheEntry) {
+        if let Some(old) = self.entries.get(url) {
+            if old.path != entry.path && old.path.starts_with(&self.dir) {
+                info!("Removing outdated cached file '{}'", old.path.display());
+                let _ = std::fs::remove_file(&old.path);
+            }
+        }
+
+        self.entries.insert(url.into(), entry);
+    }
+
+    /// Writes the index of the cache to the cache directory.
+    pub fn save(&self) -> Result<(), WebError> {
+        let content = serde_json::to_string_pretty(&self.entries).map_err(WebError::CacheIndex)?;
+
+        std::fs::write(self.dir.join(INDEX_FILE), content)?;
+
+        Ok(())
+    }
+
+    /// Downloads the file at the specified url to the cache directory, unless
+    /// the file is already cached and the server responds that the file has
+    /// not been modified since it was cached.
+    ///
+    /// ## Returns
+    ///
+    /// The path to the cached file, regardless of whether it was downloaded or
+    /// not.
+    pub fn download(&mut self, request: &WebRequest, url: &str) -> Result<PathBuf, WebError> {
+        let cached = self.get(url).cloned();
+        let (etag, last_modified) = cached.as_ref().map_or((None, None), |entry| {
+            (entry.etag.as_deref(), entry.last_modified.as_deref())
+        });
+
+        match (
+            request.get_binary_response(url, etag, last_modified)?,
+            cached,
+        ) {
+            (ResponseType::Updated(_), Some(entry)) => {
+                info!("Using cached file '{}'", entry.path.display());
+
+                Ok(entry.path)
+            }
+            (ResponseType::Updated(status), None) => Err(WebError::Other(format!(
+                "The server responded with status {} for a file that is not cached",
+                status
+            ))),
+            (ResponseType::New(mut response, _), _) => {
+                // Every url is stored in its own directory, to prevent files
+                // with the same name from overwriting each other
+                let dir = self.dir.join(url_dir_name(url));
+                std::fs::create_dir_all(&dir)?;
+                response.set_work_dir(&dir);
+                let etag = response.get_header("etag");
+                let last_modified = response.get_header("last-modified");
+                let path = response.read(None)?;
+
+                self.insert(
+                    url,
+                    CacheEntry {
+                        etag,
+                        last_modified,
+                        path: path.clone(),
+                    },
+                );
+                self.save()?;
+
+                Ok(path)
+            }
+        }
+    }
+}
+
+/// Creates a stable directory name for the specified url, using the 64bit
+/// `FNV-1a` hash of the url.
+fn url_dir_name(url: &str) -> String {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = url.bytes().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_server;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    #[test]
+    fn open_should_create_empty_cache() {
+        let dir = cache_dir("aer-web-cache-empty");
+
+        let cache = DownloadCache::open(&dir).unwrap();
+
+        assert!(dir.is_dir());
+        assert_eq!(cache.dir(), dir);
+        assert_eq!(cache.get("https://example.org/test.zip"), None);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn save_should_persist_entries_across_instances() {
+        let dir = cache_dir("aer-web-cache-persist");
+        let path = dir.join("test.zip");
+        let entry = CacheEntry {
+            etag: Some("\"abc\"".into()),
+            last_modified: None,
+            path: path.clone(),
+        };
+        {
+            let mut cache = DownloadCache::open(&dir).unwrap();
+            std::fs::write(&path, b"test").unwrap();
+            cache.insert("https://example.org/test.zip", entry.clone());
+            cache.save().unwrap();
+        }
+
+        let cache = DownloadCache::open(&dir).unwrap();
+
+        assert_eq!(cache.get("https://example.org/test.zip"), Some(&entry));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn download_should_use_cached_file_on_second_run() {
+        let dir = cache_dir("aer-web-cache-second-run");
+        let downloads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&downloads);
+        let url = test_server::serve(move |request| {
+            if request.header("if-none-match") == Some("\"abc\"") {
+                test_server::response(304, &[], b"")
+            } else {
+                counter.fetch_add(1, Ordering::SeqCst);
+                test_server::response(200, &[("ETag", "\"abc\"")], b"cached content")
+            }
+        });
+        let url = format!("{}/cached-file.bin", url);
+        let request = WebRequest::create();
+
+        let first = DownloadCache::open(&dir)
+            .unwrap()
+            .download(&request, &url)
+            .unwrap();
+        let second = DownloadCache::open(&dir)
+            .unwrap()
+            .download(&request, &url)
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+        assert_eq!(std::fs::read(&second).unwrap(), b"cached content");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn download_should_store_urls_with_same_file_name_separately() {
+        let dir = cache_dir("aer-web-cache-same-name");
+        let url =
+            test_server::serve(|request| test_server::response(200, &[], request.path.as_bytes()));
+        let first_url = format!("{}/1.0/setup.exe", url);
+        let second_url = format!("{}/2.0/setup.exe", url);
+        let request = WebRequest::create();
+        let mut cache = DownloadCache::open(&dir).unwrap();
+
+        let first = cache.download(&request, &first_url).unwrap();
+        let second = cache.download(&request, &second_url).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read(&first).unwrap(), b"/1.0/setup.exe");
+        assert_eq!(std::fs::read(&second).unwrap(), b"/2.0/setup.exe");
+        assert_eq!(cache.get(&first_url).unwrap().path, first);
+        assert_eq!(cache.get(&second_url).unwrap().path, second);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn download_should_not_overwrite_index_file() {
+        let dir = cache_dir("aer-web-cache-index-name");
+        let url = test_server::serve(|_| test_server::response(200, &[], b"not an index"));
+        let url = format!("{}/index.json", url);
+        let request = WebRequest::create();
+
+        let path = DownloadCache::open(&dir)
+            .unwrap()
+            .download(&request, &url)
+            .unwrap();
+        let cache = DownloadCache::open(&dir).unwrap();
+
+        assert_ne!(path, dir.join(INDEX_FILE));
+        assert_eq!(cache.get(&url).unwrap().path, path);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn download_should_remove_old_file_when_file_name_changes() {
+        let dir = cache_dir("aer-web-cache-renamed");
+        let url = test_server::serve(|request| {
+            if request.index == 0 {
+                test_server::response(
+                    200,
+                    &[
+                        ("ETag", "\"v1\""),
+                        (
+                            "Content-Disposition",
+                            "attachment; filename=\"tool-1.0.zip\"",
+                        ),
+                    ],
+                    b"first",
+                )
+            } else {
+                test_server::response(
+                    200,
+                    &[
+                        ("ETag", "\"v2\""),
+                        (
+                            "Content-Disposition",
+                            "attachment; filename=\"tool-2.0.zip\"",
+                        ),
+                    ],
+                    b"second",
+                )
+            }
+        });
+        let url = format!("{}/latest", url);
+        let request = WebRequest::create();
+        let mut cache = DownloadCache::open(&dir).unwrap();
+
+        let first = cache.download(&request, &url).unwrap();
+        let second = cache.download(&request, &url).unwrap();
+
+        assert_ne!(first, second);
+        assert!(!first.exists());
+        assert_eq!(std::fs::read(&second).unwrap(), b"second");
+        assert_eq!(cache.get(&url).unwrap().path, second);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn insert_should_not_remove_files_outside_cache_directory() {
+        let dir = cache_dir("aer-web-cache-outside");
+        let outside = std::env::temp_dir().join("aer-web-cache-outside.bin");
+        std::fs::write(&outside, b"outside").unwrap();
+        let mut cache = DownloadCache::open(&dir).unwrap();
+        let entry = |path: PathBuf| CacheEntry {
+            etag: None,
+            last_modified: None,
+            path,
+        };
+
+        cache.insert("https://example.org/test.zip", entry(outside.clone()));
+        cache.insert("https://example.org/test.zip", entry(dir.join("test.zip")));
+
+        assert!(outside.exists());
+
+        let _ = std::fs::remove_file(outside);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn open_should_return_cache_index_error_on_invalid_index() {
+        let dir = cache_dir("aer-web-cache-invalid-index");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(INDEX_FILE), b"{ not json").unwrap();
+
+        let err = DownloadCache::open(&dir).unwrap_err();
+
+        assert!(matches!(err, WebError::CacheIndex(_)));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn url_dir_name_should_be_stable_and_unique_per_url() {
+        assert_eq!(
+            url_dir_name("https://example.org/1.0/setup.exe"),
+            url_dir_name("https://example.org/1.0/setup.exe")
+        );
+        assert_ne!(
+            url_dir_name("https://example.org/1.0/setup.exe"),
+            url_dir_name("https://example.org/2.0/setup.exe")
+        );
+        assert_eq!(url_dir_name("").len(), 16);
+    }
+
+    #[test]
+    fn download_should_download_file_again_when_cached_file_is_missing() {
+        let dir = cache_dir("aer-web-cache-missing-file");
+        let downloads = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&downloads);
+        let url = test_server::serve(move |request| {
+            if request.header("if-none-match") == Some("\"abc\"") {
+                test_server::response(304, &[], b"")
+            } else {
+                counter.fetch_add(1, Ordering::SeqCst);
+                test_server::response(200, &[("ETag", "\"abc\"")], b"cached content")
+            }
+        });
+        let url = format!("{}/missing-file.bin", url);
+        let request = WebRequest::create();
+
+        let first = DownloadCache::open(&dir)
+            .unwrap()
+            .download(&request, &url)
+            .unwrap();
+        std::fs::remove_file(&first).unwrap();
+        let second = DownloadCache::open(&dir)
+            .unwrap()
+            .download(&request, &url)
+            .unwrap();
+
+        assert_eq!(downloads.load(Ordering::SeqCst), 2);
+        assert!(second.is_file());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Contains helpers for reading checksum files that are commonly published
+//! together with the binary files of a release (*ie: `SHA256SUMS`*).
+
+use std::collections::HashMap;
+
+/// Parses the content of a checksum file, and returns the checksums keyed by
+/// the name of the file they belong to.
+///
+/// Every line is expected to be in the format `<hex> <filename>`, where the
+/// separator can be either a single or two spaces, and the file name can
+/// optionally be prefixed with a `*` (which marks the file as a binary file).
+/// Any line that do not follow this format is ignored, and all checksums are
+/// returned in lowercase.
+///
+/// ## Examples
+///
+/// ```
+/// use aer_web::checksums::parse_checksums;
+///
+/// let checksums = parse_checksums(
+///     "5a9c5c2b0ad6dbad5cba2e71b5ba1fd3b0b1ccc1f1b6e56b04b8b2b1da06e31d  test.zip",
+/// );
+///
+/// assert_eq!(
+///     checksums.get("test.zip").map(|c| c.as_str()),
+///     Some("5a9c5c2b0ad6dbad5cba2e71b5ba1fd3b0b1ccc1f1b6e56b04b8b2b1da06e31d")
+/// );
+/// ```
+pub fn parse_checksums(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| parse_line(line.trim_end()))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let index = line.find(|c| c == ' ' || c == '\t')?;
+    let (checksum, file_name) = line.split_at(index);
+
+    if checksum.is_empty() || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let file_name = &file_name[1..];
+    let file_name = file_name
+        .strip_prefix(' ')
+        .or_else(|| file_name.strip_prefix('*'))
+        .unwrap_or(file_name);
+
+    if file_name.is_empty() {
+        None
+    } else {
+        Some((file_name.to_owned(), checksum.to_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const SAMPLE: &str = "\
+e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  test-1.0.0-x64.zip
+7D865E959B2466918C9863AFCA942D0FB89D7C9AC0C99BAFC3749504DED97730 *test-1.0.0-x86.exe
+b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c test setup.msi
+# Comments and invalid lines are ignored
+not-a-checksum  test.txt
+";
+
+    #[test]
+    fn parse_checksums_should_parse_all_valid_lines() {
+        let checksums = parse_checksums(SAMPLE);
+
+        assert_eq!(checksums.len(), 3);
+    }
+
+    #[rstest(
+        file_name,
+        expected,
+        case(
+            "test-1.0.0-x64.zip",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        ),
+        case(
+            "test-1.0.0-x86.exe",
+            "7d865e959b2466918c9863afca942d0fb89d7c9ac0c99bafc3749504ded97730"
+        ),
+        case(
+            "test setup.msi",
+            "b5bb9d8014a0f9b1d61e21e796d78dccdf1352f23cd32812f4850b878ae4944c"
+        )
+    )]
+    fn parse_checksums_should_return_expected_checksum(file_name: &str, expected: &str) {
+        let checksums = parse_checksums(SAMPLE);
+
+        assert_eq!(checksums.get(file_name).map(|c| c.as_str()), Some(expected));
+    }
+
+    #[test]
+    fn parse_checksums_should_handle_windows_line_endings() {
+        let checksums = parse_checksums("abcdef  test.zip\r\nabcdef12 *test.exe\r\n");
+
+        assert_eq!(
+            checksums.get("test.zip").map(|c| c.as_str()),
+            Some("abcdef")
+        );
+        assert_eq!(
+            checksums.get("test.exe").map(|c| c.as_str()),
+            Some("abcdef12")
+        );
+    }
+
+    #[test]
+    fn parse_checksums_should_return_empty_map_for_empty_text() {
+        let checksums = parse_checksums("");
+
+        assert!(checksums.is_empty());
+    }
+}
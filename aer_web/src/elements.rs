@@ -77,6 +77,89 @@ impl LinkType {
     }
 }
 
+/// Holds the mapping between file extensions and the [LinkType] that links
+/// ending with the extension will be classified as.
+///
+/// The default mapping classifies the following extensions:
+///
+/// - `.html`: [LinkType::Html]
+/// - `.json`: [LinkType::Json]
+/// - `.css`: [LinkType::Css]
+/// - `.txt`: [LinkType::Text]
+/// - `.zip`, `.7z`, `.exe`, `.msi`, `.tar`, `.tar.gz`, `.tar.bz2` and `.nupkg`:
+///   [LinkType::Binary]
+///
+/// ## Examples
+///
+/// Treating links to markdown files as text documents.
+/// ```
+/// use aer_web::{LinkType, LinkTypeMapping};
+/// use reqwest::Url;
+///
+/// let mut mapping = LinkTypeMapping::default();
+/// mapping.insert(".md", LinkType::Text);
+///
+/// let url = Url::parse("https://example.org/README.md").unwrap();
+/// assert_eq!(mapping.classify(&url), LinkType::Text);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkTypeMapping {
+    extensions: HashMap<String, LinkType>,
+}
+
+impl LinkTypeMapping {
+    /// Creates a new mapping without any extensions, all links will be
+    /// classified as [LinkType::Unknown] until extensions are added.
+    pub fn new() -> LinkTypeMapping {
+        LinkTypeMapping {
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// Adds or replaces the link type that links ending with the specified
+    /// extension should be classified as. The extension is compared case
+    /// insensitively, and can be specified both with and without the leading
+    /// dot.
+    pub fn insert(&mut self, extension: &str, link_type: LinkType) {
+        let extension = extension.trim_start_matches('.').to_lowercase();
+
+        if !extension.is_empty() {
+            self.extensions.insert(extension, link_type);
+        }
+    }
+
+    /// Returns the type of the specified url, based on the path of the url.
+    /// When multiple extensions matches the path, the longest extension is used
+    /// (*ie: `.tar.gz` is preferred over `.gz`*).
+    pub fn classify(&self, url: &Url) -> LinkType {
+        let path = url.path().to_lowercase();
+
+        self.extensions
+            .iter()
+            .filter(|(ext, _)| path.ends_with(&format!(".{}", ext)))
+            .max_by_key(|(ext, _)| ext.len())
+            .map_or(LinkType::Unknown, |(_, link_type)| *link_type)
+    }
+}
+
+impl Default for LinkTypeMapping {
+    /// Creates the default mapping used when parsing html pages.
+    fn default() -> LinkTypeMapping {
+        let mut mapping = LinkTypeMapping::new();
+        mapping.insert("html", LinkType::Html);
+        mapping.insert("json", LinkType::Json);
+        mapping.insert("css", LinkType::Css);
+        mapping.insert("txt", LinkType::Text);
+        for ext in &[
+            "zip", "7z", "exe", "msi", "tar", "tar.gz", "tar.bz2", "nupkg",
+        ] {
+            mapping.insert(ext, LinkType::Binary);
+        }
+
+        mapping
+    }
+}
+
 /// Stores information that are know about the current link.
 #[derive(Debug, Clone, PartialEq)]
 pub struct LinkElement {
@@ -114,6 +197,50 @@ impl LinkElement {
     pub fn is_binary(&self) -> bool {
         self.link_type == LinkType::Binary
     }
+
+    /// Returns true if the path of the link ends with one of the specified
+    /// extensions. The extensions are compared case insensitively, and can be
+    /// specified both with and without the leading dot (*ie: `.exe` or
+    /// `exe`*).
+    pub fn has_extension(&self, exts: &[&str]) -> bool {
+        let path = self.link.path().to_lowercase();
+
+        exts.iter().any(|ext| {
+            let ext = ext.trim_start_matches('.').to_lowercase();
+            !ext.is_empty() && path.ends_with(&format!(".{}", ext))
+        })
+    }
+}
+
+/// Filters the specified links, and only keeps the links that ends with one of
+/// the specified extensions (see [LinkElement::has_extension]).
+pub fn filter_by_extension(links: Vec<LinkElement>, exts: &[&str]) -> Vec<LinkElement> {
+    links
+        .into_iter()
+        .filter(|link| link.has_extension(exts))
+        .collect()
+}
+
+/// Returns the link with the highest version, links without a version are
+/// ignored. When several links share the highest version, the first of these
+/// links is returned.
+pub fn latest_by_version(links: &[LinkElement]) -> Option<&LinkElement> {
+    let mut latest: Option<(&LinkElement, &Versions)> = None;
+
+    for link in links {
+        if let Some(version) = &link.version {
+            let is_newer = match latest {
+                Some((_, latest_version)) => version > latest_version,
+                None => true,
+            };
+
+            if is_newer {
+                latest = Some((link, version));
+            }
+        }
+    }
+
+    latest.map(|(link, _)| link)
 }
 
 impl Default for LinkElement {
@@ -129,3 +256,175 @@ impl Default for LinkElement {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn sample_links() -> Vec<LinkElement> {
+        [
+            "https://example.org/releases/test-1.0.0.exe",
+            "https://example.org/releases/test-1.0.0.MSI",
+            "https://example.org/releases/test-1.0.0.zip?download=true",
+            "https://example.org/releases/test-1.0.0.tar.gz",
+            "https://example.org/releases/",
+            "https://example.org/releases/exe",
+        ]
+        .iter()
+        .map(|url| LinkElement::new(Url::parse(url).unwrap(), LinkType::Unknown))
+        .collect()
+    }
+
+    #[rstest(
+        url,
+        exts,
+        expected,
+        case("https://example.org/test.exe", &[".exe"], true),
+        case("https://example.org/test.exe", &["exe"], true),
+        case("https://example.org/test.EXE", &[".exe"], true),
+        case("https://example.org/test.exe", &[".MSI", ".Exe"], true),
+        case("https://example.org/test.exe?a=b.msi", &[".exe"], true),
+        case("https://example.org/test.exe?a=b.msi", &[".msi"], false),
+        case("https://example.org/test.tar.gz", &[".gz"], true),
+        case("https://example.org/test.tar.gz", &[".tar.gz"], true),
+        case("https://example.org/testexe", &[".exe"], false),
+        case("https://example.org/test.exe", &[], false),
+        case("https://example.org/test.exe", &["", "."], false)
+    )]
+    fn has_extension_should_match_expected_extensions(url: &str, exts: &[&str], expected: bool) {
+        let link = LinkElement::new(Url::parse(url).unwrap(), LinkType::Unknown);
+
+        assert_eq!(link.has_extension(exts), expected);
+    }
+
+    #[rstest(
+        url,
+        expected,
+        case("https://example.org/test.exe", LinkType::Binary),
+        case("https://example.org/test.msi", LinkType::Binary),
+        case("https://example.org/test.zip", LinkType::Binary),
+        case("https://example.org/test.tar.gz", LinkType::Binary),
+        case("https://example.org/test.ZIP?download=1", LinkType::Binary),
+        case("https://example.org/index.html", LinkType::Html),
+        case("https://example.org/data.json", LinkType::Json),
+        case("https://example.org/style.css", LinkType::Css),
+        case("https://example.org/notes.txt", LinkType::Text),
+        case("https://example.org/test.gz", LinkType::Unknown),
+        case("https://example.org/releases", LinkType::Unknown)
+    )]
+    fn classify_should_use_expected_default_link_types(url: &str, expected: LinkType) {
+        let mapping = LinkTypeMapping::default();
+
+        let actual = mapping.classify(&Url::parse(url).unwrap());
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn classify_should_use_custom_link_types() {
+        let mut mapping = LinkTypeMapping::default();
+        mapping.insert(".msi", LinkType::Unknown);
+        mapping.insert("GZ", LinkType::Binary);
+        mapping.insert(".tar.gz", LinkType::Text);
+
+        assert_eq!(
+            mapping.classify(&Url::parse("https://example.org/test.msi").unwrap()),
+            LinkType::Unknown
+        );
+        assert_eq!(
+            mapping.classify(&Url::parse("https://example.org/test.gz").unwrap()),
+            LinkType::Binary
+        );
+        assert_eq!(
+            mapping.classify(&Url::parse("https://example.org/test.tar.gz").unwrap()),
+            LinkType::Text
+        );
+    }
+
+    #[test]
+    fn classify_should_return_unknown_for_empty_mapping() {
+        let mapping = LinkTypeMapping::new();
+
+        let actual = mapping.classify(&Url::parse("https://example.org/test.exe").unwrap());
+
+        assert_eq!(actual, LinkType::Unknown);
+    }
+
+    #[test]
+    fn filter_by_extension_should_only_keep_matching_links() {
+        let expected = vec![
+            "https://example.org/releases/test-1.0.0.exe",
+            "https://example.org/releases/test-1.0.0.MSI",
+            "https://example.org/releases/test-1.0.0.zip?download=true",
+        ];
+
+        let actual: Vec<String> = filter_by_extension(sample_links(), &[".exe", ".msi", ".zip"])
+            .into_iter()
+            .map(|link| link.link.to_string())
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn filter_by_extension_should_return_empty_vec_when_nothing_matches() {
+        let actual = filter_by_extension(sample_links(), &[".7z"]);
+
+        assert!(actual.is_empty());
+    }
+
+    fn versioned_link(url: &str, version: Option<&str>) -> LinkElement {
+        LinkElement {
+            link: Url::parse(url).unwrap(),
+            version: version.map(|v| Versions::parse(v).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn latest_by_version_should_return_link_with_highest_version() {
+        let links = vec![
+            versioned_link("https://example.org/test-1.0.0.exe", Some("1.0.0")),
+            versioned_link("https://example.org/releases", None),
+            versioned_link("https://example.org/test-2.1.0.exe", Some("2.1.0")),
+            versioned_link(
+                "https://example.org/test-2.1.0-beta.1.exe",
+                Some("2.1.0-beta.1"),
+            ),
+            versioned_link("https://example.org/test-1.5.0.exe", Some("1.5.0")),
+        ];
+
+        let actual = latest_by_version(&links).unwrap();
+
+        assert_eq!(actual.link.as_str(), "https://example.org/test-2.1.0.exe");
+    }
+
+    #[test]
+    fn latest_by_version_should_return_first_link_on_ties() {
+        let links = vec![
+            versioned_link("https://example.org/test-2.0.0.exe", Some("2.0.0")),
+            versioned_link("https://example.org/test-2.0.0.msi", Some("2.0.0")),
+        ];
+
+        let actual = latest_by_version(&links).unwrap();
+
+        assert_eq!(actual.link.as_str(), "https://example.org/test-2.0.0.exe");
+    }
+
+    #[test]
+    fn latest_by_version_should_return_none_when_no_versions_exist() {
+        let links = vec![
+            versioned_link("https://example.org/releases", None),
+            versioned_link("https://example.org/downloads", None),
+        ];
+
+        assert_eq!(latest_by_version(&links), None);
+    }
+
+    #[test]
+    fn latest_by_version_should_return_none_on_empty_links() {
+        assert_eq!(latest_by_version(&[]), None);
+    }
+}
@@ -12,19 +12,72 @@ use std::fmt::Display;
 pub enum WebError {
     /// An error happened when trying to request a web site.
     Request(reqwest::Error),
+    /// The server responded with a status code that is not a success status
+    /// code (`2xx`).
+    HttpStatus {
+        /// The status code the server responded with.
+        code: u16,
+        /// The url that the status code was returned for.
+        url: reqwest::Url,
+    },
     /// An error that occurred while reading or writing to the file system
     IoError(std::io::Error),
+    /// The downloaded file did not contain the amount of bytes that the server
+    /// said it would send.
+    IncompleteDownload {
+        /// The length advertised by the server.
+        expected: u64,
+        /// The amount of bytes actually written.
+        actual: u64,
+    },
+    /// The specified regular expression could not be compiled.
+    InvalidRegex {
+        /// The regular expression that failed to compile.
+        pattern: String,
+        /// The error that was returned when compiling the regular expression.
+        error: String,
+    },
+    /// The index of the download cache could not be read or written.
+    #[cfg(feature = "cache")]
+    CacheIndex(serde_json::Error),
     /// Any other type of error not covered by the other types.
     Other(String),
 }
 
-impl Error for WebError {}
+impl Error for WebError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WebError::Request(err) => Some(err),
+            WebError::IoError(err) => Some(err),
+            #[cfg(feature = "cache")]
+            WebError::CacheIndex(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl Display for WebError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         match self {
             WebError::Request(err) => err.fmt(f),
+            WebError::HttpStatus { code, url } => write!(
+                f,
+                "The server responded with status code {} for '{}'",
+                code, url
+            ),
             WebError::IoError(err) => err.fmt(f),
+            WebError::IncompleteDownload { expected, actual } => write!(
+                f,
+                "The download was incomplete, expected {} bytes but received {} bytes",
+                expected, actual
+            ),
+            WebError::InvalidRegex { pattern, error } => write!(
+                f,
+                "The regular expression '{}' is not valid: {}",
+                pattern, error
+            ),
+            #[cfg(feature = "cache")]
+            WebError::CacheIndex(err) => write!(f, "The cache index is not valid: {}", err),
             WebError::Other(val) => f.write_str(&val),
         }
     }
@@ -24,10 +24,30 @@
 
 mod elements;
 
+#[cfg(feature = "async")]
+pub mod async_request;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod checksums;
 pub mod errors;
+#[cfg(any(feature = "blocking", feature = "async"))]
 pub mod request;
+#[cfg(any(feature = "blocking", feature = "async"))]
 pub mod response;
+#[cfg(any(test, feature = "test_server"))]
+#[doc(hidden)]
+pub mod test_server;
 
-pub use elements::{LinkElement, LinkType};
+#[cfg(feature = "async")]
+pub use async_request::AsyncWebRequest;
+#[cfg(feature = "cache")]
+pub use cache::DownloadCache;
+pub use elements::{
+    filter_by_extension, latest_by_version, LinkElement, LinkType, LinkTypeMapping,
+};
+#[cfg(any(feature = "blocking", feature = "async"))]
+pub use request::ConditionalRequest;
+#[cfg(feature = "blocking")]
 pub use request::WebRequest;
+#[cfg(feature = "blocking")]
 pub use response::WebResponse;
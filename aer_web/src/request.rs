@@ -4,25 +4,35 @@
 //! Section responsible for allowing requests to be sent to remote locations.
 
 use std::collections::HashMap;
+use std::path::Path;
+#[cfg(feature = "blocking")]
+use std::time::Duration;
 
 use lazy_static::lazy_static;
-use log::info;
-use reqwest::blocking::{Client, Response};
+use log::{info, warn};
+#[cfg(feature = "blocking")]
+use reqwest::blocking::{Client, RequestBuilder, Response};
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::{header, StatusCode, Url};
 
+#[cfg(feature = "blocking")]
+use crate::checksums::parse_checksums;
 use crate::errors::WebError;
-use crate::response::{BinaryResponse, HtmlResponse, ResponseType};
+#[cfg(feature = "blocking")]
+use crate::response::{BinaryResponse, HeadResponse, HtmlResponse, ResponseType, WebResponse};
 
 /// The name of the application + the version, which should be sent with every
 /// request to the websites.
-const APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+pub(crate) const APP_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
 lazy_static! {
-    static ref ACCEPTED_TYPES: HashMap<&'static str, &'static str> = {
+    /// The `Accept` header values sent for the different kinds of requests.
+    pub(crate) static ref ACCEPTED_TYPES: HashMap<&'static str, &'static str> = {
         let mut map = HashMap::new();
         map.insert("html", "text/html; charset=UTF-8");
         map.insert("binary", "application/octet-stream");
+        map.insert("text", "text/plain");
 
         map
     };
@@ -43,8 +53,20 @@ lazy_static! {
 ///     .get_html_response("https://httpbin.org/get")
 ///     .unwrap();
 /// ```
+#[cfg(feature = "blocking")]
 pub struct WebRequest {
     client: Client,
+    credentials: Option<Credentials>,
+}
+
+/// The credentials that will be sent with every request, these are never
+/// logged.
+pub(crate) enum Credentials {
+    Basic {
+        user: String,
+        password: Option<String>,
+    },
+    Bearer(String),
 }
 
 macro_rules! headers {
@@ -58,24 +80,52 @@ macro_rules! headers {
     };
 }
 
+#[cfg(feature = "blocking")]
 impl WebRequest {
     /// Creates a new instance of a web request. This also creates a client with
     /// the information set to the current application+version, a do not track
     /// header and a header requesting to upgrade insecure requests.
+    ///
+    /// Responses that are encoded with `gzip` will by default be transparently
+    /// decoded, see [with_decompression](WebRequest::with_decompression) for
+    /// disabling this behavior.
     pub fn create() -> WebRequest {
-        let mut client = Client::builder()
-            .user_agent(APP_USER_AGENT)
-            .default_headers(headers!(
-                header::ACCEPT_LANGUAGE => "en-US, en;q=0.8, *;q=0.5",
-                header::DNT => "1",
-                header::UPGRADE_INSECURE_REQUESTS => "1"
-            ));
-        if cfg!(windows) {
-            client = client.use_rustls_tls();
+        WebRequest {
+            client: build_client(true),
+            credentials: None,
+        }
+    }
+
+    /// Sets whether responses that are encoded with `gzip` should be decoded
+    /// before being read (the default), or if the raw encoded bytes should be
+    /// kept. Disabling the decompression is useful when downloading artifacts
+    /// that are expected to stay compressed, like `.gz` archives.
+    pub fn with_decompression(self, decompress: bool) -> WebRequest {
+        WebRequest {
+            client: build_client(decompress),
+            ..self
+        }
+    }
+
+    /// Sets the user and password that will be sent using basic
+    /// authentication with every request, replacing any previously set
+    /// credentials.
+    pub fn basic_auth(self, user: &str, password: Option<&str>) -> WebRequest {
+        WebRequest {
+            credentials: Some(Credentials::Basic {
+                user: user.into(),
+                password: password.map(Into::into),
+            }),
+            ..self
         }
+    }
 
+    /// Sets the token that will be sent as a bearer token with every request,
+    /// replacing any previously set credentials.
+    pub fn bearer_token(self, token: &str) -> WebRequest {
         WebRequest {
-            client: client.build().unwrap(),
+            credentials: Some(Credentials::Bearer(token.into())),
+            ..self
         }
     }
 
@@ -88,9 +138,7 @@ impl WebRequest {
     pub fn get_html_response(&self, url: &str) -> Result<HtmlResponse, WebError> {
         let url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
 
-        let client = &self.client;
-
-        let response = client
+        let response = self
             .get(url)
             .header(header::ACCEPT, ACCEPTED_TYPES["html"])
             .send()
@@ -99,6 +147,23 @@ impl WebRequest {
         handle_exit_code(response, HtmlResponse::new)
     }
 
+    /// Downloads and parses a checksum file (*ie: `SHA256SUMS`*), returning the
+    /// checksums keyed by the name of the file they belong to. See
+    /// [parse_checksums](crate::checksums::parse_checksums) for the supported
+    /// format.
+    pub fn get_checksums(&self, url: &str) -> Result<HashMap<String, String>, WebError> {
+        let url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+
+        let response = self
+            .get(url)
+            .header(header::ACCEPT, ACCEPTED_TYPES["text"])
+            .send()
+            .map_err(WebError::Request)?;
+        let text = check_status(response)?.text().map_err(WebError::Request)?;
+
+        Ok(parse_checksums(&text))
+    }
+
     /// Makes a request to a web endpoint and requests a result in the type of a
     /// binary without downloading the actual upstream content. If an etag
     /// or last_modified argument is specified, these will be sent along with
@@ -125,37 +190,158 @@ impl WebRequest {
         etag: Option<&str>,
         last_modified: Option<&str>,
     ) -> Result<ResponseType<BinaryResponse>, WebError> {
-        let url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+        self.get_conditional_binary_response(url, &ConditionalRequest::new(etag, last_modified))
+    }
 
-        let client = &self.client;
-        let headers = {
-            let mut headers = HeaderMap::new();
-            headers.insert(
-                header::ACCEPT,
-                HeaderValue::from_static(ACCEPTED_TYPES["binary"]),
+    /// Makes a request to a web endpoint the same way as
+    /// [get_binary_response](WebRequest::get_binary_response), but takes the
+    /// values of the previous download bundled in a [ConditionalRequest].
+    ///
+    /// When the conditional request holds the size of a partial file (see
+    /// [ConditionalRequest::with_partial_file]) together with an etag or last
+    /// modified value, only the remaining content is requested. The etag (*or
+    /// the last modified value*) is sent as the `If-Range` header, which makes
+    /// the server respond with the full file if it have changed since the
+    /// partial file was written. Without either value the full file is always
+    /// requested.
+    ///
+    /// If the server responds with `416 Range Not Satisfiable` and the size of
+    /// the remote file equals the partial file, the partial file is already
+    /// complete and a [ResponseType::Updated] is returned. Any other
+    /// unexpected range response causes the full file to be requested.
+    ///
+    /// Local `file://` urls are not requested, instead the local file is copied
+    /// when the response is read (*mainly useful when testing against local
+    /// fixtures*). The conditional values are ignored for local files.
+    pub fn get_conditional_binary_response(
+        &self,
+        url: &str,
+        conditional: &ConditionalRequest,
+    ) -> Result<ResponseType<BinaryResponse>, WebError> {
+        let parsed_url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+        if parsed_url.scheme() == "file" {
+            return BinaryResponse::from_file(parsed_url)
+                .map(|rsp| ResponseType::New(rsp, StatusCode::OK.as_u16()));
+        }
+
+        let headers = conditional.binary_headers()?;
+        // Retried downloads always request the full file, as any partial
+        // content written by the failed attempt is not kept
+        let retry_request = Some(self.get(parsed_url.clone()).headers(headers.clone()));
+
+        let mut request = self.get(parsed_url.clone()).headers(headers);
+        let range = conditional.range_headers()?;
+        if let Some((_, range_headers)) = &range {
+            request = request.headers(range_headers.clone());
+        }
+        let response = request.send().map_err(WebError::Request)?;
+        let status = response.status();
+
+        match classify_binary_response(status, response.headers(), range.map(|(start, _)| start)) {
+            BinaryResponseKind::Updated => Ok(ResponseType::Updated(status.as_u16())),
+            BinaryResponseKind::Restart => self.get_full_binary_response(url),
+            BinaryResponseKind::Resume(start) => handle_exit_code(response, move |rsp| {
+                ResponseType::New(
+                    BinaryResponse::new(rsp, parsed_url)
+                        .with_request(retry_request)
+                        .with_range_start(Some(start)),
+                    status.as_u16(),
+                )
+            }),
+            BinaryResponseKind::Full => handle_exit_code(response, move |rsp| {
+                ResponseType::New(
+                    BinaryResponse::new(rsp, parsed_url).with_request(retry_request),
+                    status.as_u16(),
+                )
+            }),
+        }
+    }
+
+    /// Requests the full binary file, used when a partial file can not be
+    /// resumed. The etag and last modified values of the partial file are not
+    /// sent, as a not modified response would leave the partial file as-is.
+    fn get_full_binary_response(
+        &self,
+        url: &str,
+    ) -> Result<ResponseType<BinaryResponse>, WebError> {
+        self.get_conditional_binary_response(url, &ConditionalRequest::default())
+    }
+
+    /// Makes a request to a web endpoint the same way as
+    /// [get_conditional_binary_response](WebRequest::get_conditional_binary_response),
+    /// but retries the request if a network error occurs or the server
+    /// responds with a server error (`5xx`).
+    ///
+    /// ## Arguments
+    ///
+    /// - `url`: The url to the binary file that should possibly be downloaded.
+    /// - `conditional`: The values of the previous download, sent with every
+    ///   attempt.
+    /// - `attempts`: The maximum number of times the request will be sent,
+    ///   including the first attempt. Must be at least `1`.
+    /// - `backoff`: The time to wait before the first retry, the time will be
+    ///   doubled for every following retry.
+    ///
+    /// ## Returns
+    ///
+    /// The response of the first successful attempt. If all of the attempts
+    /// fail, the error of the last attempt is returned. An error is returned
+    /// without sending any request if `attempts` is `0`.
+    ///
+    /// ## Notes
+    ///
+    /// - Failures that happen while reading the content of the response can be
+    ///   retried using
+    ///   [read_with_retry](crate::response::BinaryResponse::read_with_retry).
+    pub fn get_binary_response_with_retry(
+        &self,
+        url: &str,
+        conditional: &ConditionalRequest,
+        attempts: u32,
+        backoff: Duration,
+    ) -> Result<ResponseType<BinaryResponse>, WebError> {
+        check_attempts(attempts)?;
+        let mut result = self.get_conditional_binary_response(url, conditional);
+        let mut attempt = 1;
+        let mut wait = backoff;
+
+        while attempt < attempts && result.as_ref().err().map_or(false, is_transient_error) {
+            attempt += 1;
+
+            warn!(
+                "Request to '{}' failed, retrying in {:?} (attempt {} of {})",
+                url, wait, attempt, attempts
             );
-            if let Some(etag) = etag {
-                let new_etag = format!("\"{}\"", etag.trim_matches('"'));
-
-                headers.insert(
-                    header::IF_NONE_MATCH,
-                    HeaderValue::from_str(&new_etag)
-                        .map_err(|err| WebError::Other(err.to_string()))?,
-                );
-            }
-            if let Some(last_modified) = last_modified {
-                headers.insert(
-                    header::IF_MODIFIED_SINCE,
-                    HeaderValue::from_str(last_modified)
-                        .map_err(|err| WebError::Other(err.to_string()))?,
-                );
-            }
+            std::thread::sleep(wait);
+            wait *= 2;
 
-            headers
-        };
+            result = self.get_conditional_binary_response(url, conditional);
+        }
+
+        result
+    }
+
+    /// Makes a `HEAD` request to a web endpoint, returning only the headers of
+    /// the remote file without downloading it. This is useful for checking if
+    /// a remote file have changed, or for resolving the name of the remote
+    /// file.
+    ///
+    /// The `etag` and `last_modified` arguments are handled the same way as in
+    /// [get_binary_response](WebRequest::get_binary_response), and will return
+    /// a [ResponseType::Updated] if the server responds with a not modified
+    /// response.
+    pub fn head(
+        &self,
+        url: &str,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<ResponseType<HeadResponse>, WebError> {
+        let url = Url::parse(url).map_err(|err| WebError::Other(err.to_string()))?;
+        let headers =
+            ConditionalRequest::new(etag, last_modified).headers(ACCEPTED_TYPES["binary"])?;
 
-        let response = client
-            .get(url.clone())
+        let response = self
+            .with_credentials(self.client.head(url))
             .headers(headers)
             .send()
             .map_err(WebError::Request)?;
@@ -167,22 +353,293 @@ impl WebRequest {
             Ok(ResponseType::Updated(status.as_u16()))
         } else {
             handle_exit_code(response, move |rsp| {
-                ResponseType::New(BinaryResponse::new(rsp, url), status.as_u16())
+                ResponseType::New(HeadResponse::new(rsp), status.as_u16())
             })
         }
     }
+
+    fn get(&self, url: Url) -> RequestBuilder {
+        self.with_credentials(self.client.get(url))
+    }
+
+    fn with_credentials(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.credentials {
+            Some(Credentials::Basic { user, password }) => {
+                request.basic_auth(user, password.as_ref())
+            }
+            Some(Credentials::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+/// Holds the values returned by a server for a previously downloaded file,
+/// which can be used to make a conditional request that only downloads the
+/// file again if it have changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionalRequest {
+    /// The etag previously returned by the server, sent as the
+    /// `If-None-Match` header.
+    pub etag: Option<String>,
+    /// The last modified date previously returned by the server, sent as the
+    /// `If-Modified-Since` header.
+    pub last_modified: Option<String>,
+    /// The size of the previously downloaded file, this is not sent to the
+    /// server but can be used to validate an existing file.
+    pub size: Option<u64>,
+    /// The size of a partially downloaded file, when set the remaining content
+    /// is requested using a `Range` header. The range is only requested when
+    /// the etag or last modified value is also set.
+    pub range_start: Option<u64>,
+}
+
+impl ConditionalRequest {
+    /// Creates a new conditional request with the specified etag and last
+    /// modified values.
+    pub fn new(etag: Option<&str>, last_modified: Option<&str>) -> ConditionalRequest {
+        ConditionalRequest {
+            etag: etag.map(Into::into),
+            last_modified: last_modified.map(Into::into),
+            size: None,
+            range_start: None,
+        }
+    }
+
+    /// Creates a new conditional request from the `ETag`, `Last-Modified` and
+    /// `Content-Length` headers returned in the specified response.
+    #[cfg(feature = "blocking")]
+    pub fn from_response<T: WebResponse>(response: &T) -> ConditionalRequest {
+        ConditionalRequest {
+            etag: response.get_header(header::ETAG.as_str()),
+            last_modified: response.get_header(header::LAST_MODIFIED.as_str()),
+            size: response.content_length(),
+            range_start: None,
+        }
+    }
+
+    /// Creates a new conditional request from the `ETag`, `Last-Modified` and
+    /// `Content-Length` values in the specified headers (*ie: the headers of an
+    /// asynchronous binary response*).
+    pub fn from_headers(headers: &HeaderMap) -> ConditionalRequest {
+        let get_header = |name: header::HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+
+        ConditionalRequest {
+            etag: get_header(header::ETAG),
+            last_modified: get_header(header::LAST_MODIFIED),
+            size: get_header(header::CONTENT_LENGTH).and_then(|len| len.parse().ok()),
+            range_start: None,
+        }
+    }
+
+    /// Requests only the remaining content of the specified partially
+    /// downloaded file, if the file exists and is not empty.
+    ///
+    /// The partial file must be the same file that the response is read into,
+    /// see [BinaryResponse::read](crate::response::BinaryResponse::read).
+    pub fn with_partial_file(self, path: &Path) -> ConditionalRequest {
+        let range_start = path
+            .metadata()
+            .ok()
+            .filter(|m| m.is_file() && m.len() > 0)
+            .map(|m| m.len());
+
+        ConditionalRequest {
+            range_start,
+            ..self
+        }
+    }
+
+    /// Returns the start of the range that should be requested, together with
+    /// the validator that is sent as the `If-Range` header. The etag is
+    /// preferred over the last modified value, and no range is returned when
+    /// neither value is available.
+    fn range(&self) -> Option<(u64, String)> {
+        let start = self.range_start.filter(|start| *start > 0)?;
+        let validator = match (&self.etag, &self.last_modified) {
+            (Some(etag), _) => quote_etag(etag),
+            (None, Some(last_modified)) => last_modified.clone(),
+            (None, None) => return None,
+        };
+
+        Some((start, validator))
+    }
+
+    /// Creates the `Range` and `If-Range` headers for resuming a partial file,
+    /// together with the start of the range. Returns `None` when no range
+    /// should be requested (see [range](ConditionalRequest::range)).
+    pub(crate) fn range_headers(&self) -> Result<Option<(u64, HeaderMap)>, WebError> {
+        let (start, validator) = match self.range() {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::RANGE,
+            HeaderValue::from_str(&format!("bytes={}-", start))
+                .map_err(|err| WebError::Other(err.to_string()))?,
+        );
+        headers.insert(
+            header::IF_RANGE,
+            HeaderValue::from_str(&validator).map_err(|err| WebError::Other(err.to_string()))?,
+        );
+
+        Ok(Some((start, headers)))
+    }
+
+    /// Creates the headers for a binary request, without any range headers.
+    /// The etag and last modified values describe the partial file when
+    /// resuming, and are then only sent as the validator of the range.
+    pub(crate) fn binary_headers(&self) -> Result<HeaderMap, WebError> {
+        if self.range().is_some() {
+            ConditionalRequest::default().headers(ACCEPTED_TYPES["binary"])
+        } else {
+            self.headers(ACCEPTED_TYPES["binary"])
+        }
+    }
+
+    /// Creates the headers necessary for the conditional request, the
+    /// conditional headers are only added for the values that are set.
+    pub(crate) fn headers(&self, accept: &'static str) -> Result<HeaderMap, WebError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(accept));
+        if let Some(etag) = &self.etag {
+            headers.insert(
+                header::IF_NONE_MATCH,
+                HeaderValue::from_str(&quote_etag(etag))
+                    .map_err(|err| WebError::Other(err.to_string()))?,
+            );
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.insert(
+                header::IF_MODIFIED_SINCE,
+                HeaderValue::from_str(last_modified)
+                    .map_err(|err| WebError::Other(err.to_string()))?,
+            );
+        }
+
+        Ok(headers)
+    }
+}
+
+fn quote_etag(etag: &str) -> String {
+    format!("\"{}\"", etag.trim_matches('"'))
+}
+
+#[cfg(feature = "blocking")]
+fn build_client(decompress: bool) -> Client {
+    let mut client = Client::builder()
+        .user_agent(APP_USER_AGENT)
+        .default_headers(default_headers())
+        .gzip(decompress);
+    if cfg!(windows) {
+        client = client.use_rustls_tls();
+    }
+
+    client.build().unwrap()
+}
+
+/// The headers that should be sent with every request, regardless of the
+/// client being used.
+pub(crate) fn default_headers() -> HeaderMap {
+    headers!(
+        header::ACCEPT_LANGUAGE => "en-US, en;q=0.8, *;q=0.5",
+        header::DNT => "1",
+        header::UPGRADE_INSECURE_REQUESTS => "1"
+    )
+}
+
+/// Describes how the response of a binary request should be handled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BinaryResponseKind {
+    /// The file have not been modified, or the partial file is already
+    /// complete.
+    Updated,
+    /// The partial file can not be resumed, and the full file should be
+    /// requested instead.
+    Restart,
+    /// The response holds the remaining content of the partial file, starting
+    /// at the specified position.
+    Resume(u64),
+    /// The response should be handled as a normal response.
+    Full,
+}
+
+/// Decides how the response of a binary request should be handled, based on
+/// the status and headers of the response and the start of the requested
+/// range. Shared by both the blocking and asynchronous requests.
+pub(crate) fn classify_binary_response(
+    status: StatusCode,
+    headers: &HeaderMap,
+    range_start: Option<u64>,
+) -> BinaryResponseKind {
+    match (status, range_start) {
+        (StatusCode::NOT_MODIFIED, _) => {
+            info!("The web server responded with status: {}!", status);
+
+            BinaryResponseKind::Updated
+        }
+        (StatusCode::RANGE_NOT_SATISFIABLE, Some(start))
+            if content_range_total(headers) == Some(start) =>
+        {
+            info!("The partial file is already complete, nothing to resume!");
+
+            BinaryResponseKind::Updated
+        }
+        (StatusCode::RANGE_NOT_SATISFIABLE, Some(_)) => {
+            warn!("The partial file does not match the remote file, downloading full file!");
+
+            BinaryResponseKind::Restart
+        }
+        (StatusCode::PARTIAL_CONTENT, Some(start)) if !content_range_matches(headers, start) => {
+            warn!("The server responded with an unexpected range, downloading full file!");
+
+            BinaryResponseKind::Restart
+        }
+        (StatusCode::PARTIAL_CONTENT, Some(start)) => {
+            info!("Resuming download from byte {}", start);
+
+            BinaryResponseKind::Resume(start)
+        }
+        _ => BinaryResponseKind::Full,
+    }
+}
+
+/// Returns the total size of the remote file from the `Content-Range` header
+/// of a `416 Range Not Satisfiable` response (*ie: `bytes */1234`*).
+fn content_range_total(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes */")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Checks that the `Content-Range` header of a partial response starts at the
+/// requested position.
+fn content_range_matches(headers: &HeaderMap, start: u64) -> bool {
+    let expected_range = format!("bytes {}-", start);
+
+    headers
+        .get(header::CONTENT_RANGE)
+        .and_then(|r| r.to_str().ok())
+        .map_or(false, |r| r.starts_with(&expected_range))
 }
 
+#[cfg(feature = "blocking")]
 fn handle_exit_code<T, F: FnOnce(Response) -> T>(
     response: Response,
     creation: F,
 ) -> Result<T, WebError> {
-    if !response.status().is_success() {
-        return match response.error_for_status() {
-            Err(err) => Err(WebError::Request(err)),
-            Ok(_) => unreachable!(),
-        };
-    }
+    let response = check_status(response)?;
 
     info!(
         "The web server responded with status: {}!",
@@ -192,12 +649,95 @@ fn handle_exit_code<T, F: FnOnce(Response) -> T>(
     Ok(creation(response))
 }
 
-#[cfg(test)]
+/// Returns an error if the specified number of attempts would never send any
+/// request.
+#[cfg(feature = "blocking")]
+pub(crate) fn check_attempts(attempts: u32) -> Result<(), WebError> {
+    if attempts == 0 {
+        Err(WebError::Other(
+            "The number of attempts must be at least 1".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks whether the error is likely to be temporary, and that the request
+/// should be retried. This is the case for timeouts, connection failures,
+/// failures while reading the body, server errors (`5xx`) and incomplete
+/// downloads.
+#[cfg(feature = "blocking")]
+pub(crate) fn is_transient_error(err: &WebError) -> bool {
+    match err {
+        WebError::Request(err) => {
+            err.is_timeout()
+                || err.is_connect()
+                || err.is_body()
+                || err.status().map_or(false, |s| s.is_server_error())
+        }
+        WebError::HttpStatus { code, .. } => *code >= 500,
+        WebError::IncompleteDownload { .. } => true,
+        _ => false,
+    }
+}
+
+/// Returns the response unchanged if the server responded with a success status
+/// code, otherwise a [WebError::HttpStatus] error is returned.
+#[cfg(feature = "blocking")]
+pub(crate) fn check_status(response: Response) -> Result<Response, WebError> {
+    check_status_code(response.status(), response.url())?;
+
+    Ok(response)
+}
+
+/// Returns a [WebError::HttpStatus] error if the specified status is not a
+/// success status code. Shared by both the blocking and asynchronous requests.
+pub(crate) fn check_status_code(status: StatusCode, url: &Url) -> Result<(), WebError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(WebError::HttpStatus {
+            code: status.as_u16(),
+            url: url.clone(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use reqwest::StatusCode;
+    use rstest::rstest;
 
     use super::*;
     use crate::response::*;
+    use crate::test_server;
+
+    const GZIP_CONTENT: &[u8] = &[
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xf3, 0x48, 0xcd, 0xc9, 0xc9,
+        0x57, 0x48, 0x2b, 0xca, 0xcf, 0x55, 0x48, 0x54, 0x48, 0xaf, 0xca, 0x2c, 0x50, 0x48, 0xcd,
+        0x4b, 0xce, 0x4f, 0x49, 0x4d, 0x51, 0x28, 0x4a, 0x2d, 0x2e, 0xc8, 0xcf, 0x2b, 0x4e, 0x55,
+        0x04, 0x00, 0xb0, 0xca, 0x9a, 0xc5, 0x23, 0x00, 0x00, 0x00,
+    ];
+
+    fn download_gzip_content(request: WebRequest, file_name: &str) -> Vec<u8> {
+        let url = test_server::serve(|_| {
+            test_server::response(200, &[("Content-Encoding", "gzip")], GZIP_CONTENT)
+        });
+        let mut response = match request
+            .get_binary_response(&format!("{}/{}", url, file_name), None, None)
+            .unwrap()
+        {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&std::env::temp_dir());
+
+        let path = response.read(None).unwrap();
+        let content = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(path);
+
+        content
+    }
 
     #[test]
     fn create_should_build_client_with_expected_values() {
@@ -207,6 +747,24 @@ mod tests {
         // not expect.
     }
 
+    #[test]
+    fn create_should_decode_gzip_encoded_responses() {
+        let request = WebRequest::create();
+
+        let content = download_gzip_content(request, "gzip-decoded-test.txt");
+
+        assert_eq!(content, b"Hello from a gzip encoded response!");
+    }
+
+    #[test]
+    fn with_decompression_should_keep_gzip_encoded_responses_when_disabled() {
+        let request = WebRequest::create().with_decompression(false);
+
+        let content = download_gzip_content(request, "gzip-raw-test.txt.gz");
+
+        assert_eq!(content, GZIP_CONTENT);
+    }
+
     #[test]
     fn get_html_response_should_create_response() {
         let url = Url::parse("https://httpbin.org/get").unwrap();
@@ -219,7 +777,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Status(404)")]
+    #[should_panic(expected = "HttpStatus { code: 404")]
     fn get_html_response_should_give_error_on_404_status_code() {
         let request = WebRequest::create();
 
@@ -229,7 +787,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Status(500)")]
+    #[should_panic(expected = "HttpStatus { code: 500")]
     fn get_html_response_should_give_error_on_error_response() {
         let request = WebRequest::create();
 
@@ -239,7 +797,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Status(404)")]
+    #[should_panic(expected = "HttpStatus { code: 404")]
     fn get_binary_response_should_give_error_on_404_status_code() {
         let request = WebRequest::create();
 
@@ -249,7 +807,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Status(500)")]
+    #[should_panic(expected = "HttpStatus { code: 500")]
     fn get_binary_response_should_give_error_on_error_response() {
         let request = WebRequest::create();
 
@@ -258,6 +816,395 @@ mod tests {
             .unwrap();
     }
 
+    #[rstest(status, case(404), case(500))]
+    fn get_html_response_should_return_http_status_error_with_body(status: u16) {
+        let url = test_server::serve(move |_| {
+            test_server::response(status, &[], b"<html><body>Error page</body></html>")
+        });
+        let request = WebRequest::create();
+
+        let err = request
+            .get_html_response(&format!("{}/error.html", url))
+            .unwrap_err();
+
+        match err {
+            WebError::HttpStatus { code, url: err_url } => {
+                assert_eq!(code, status);
+                assert_eq!(err_url.as_str(), format!("{}/error.html", url));
+            }
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+
+    #[rstest(status, case(404), case(500))]
+    fn get_binary_response_should_return_http_status_error_with_body(status: u16) {
+        let url = test_server::serve(move |_| test_server::response(status, &[], b"Error content"));
+        let request = WebRequest::create();
+
+        let err = request
+            .get_binary_response(&format!("{}/error.bin", url), None, None)
+            .unwrap_err();
+
+        match err {
+            WebError::HttpStatus { code, url: err_url } => {
+                assert_eq!(code, status);
+                assert_eq!(err_url.as_str(), format!("{}/error.bin", url));
+            }
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+
+    fn head_server() -> String {
+        test_server::serve(|request| {
+            if request.method != "HEAD" {
+                test_server::response(500, &[], b"")
+            } else if request.header("if-none-match") == Some("\"abc\"") {
+                test_server::response(304, &[], b"")
+            } else {
+                test_server::response(200, &[("ETag", "\"abc\""), ("Content-Length", "16")], b"")
+            }
+        })
+    }
+
+    #[test]
+    fn head_should_return_headers_without_content() {
+        let url = head_server();
+        let request = WebRequest::create();
+
+        let response = request
+            .head(&format!("{}/downloads/test-file.zip", url), None, None)
+            .unwrap();
+
+        let response = match response {
+            ResponseType::New(response, 200) => response,
+            _ => panic!("Expected a new response with status 200!"),
+        };
+        assert_eq!(response.file_name(), Some("test-file.zip".into()));
+        assert_eq!(response.content_length(), Some(16));
+        let headers = response.read(None).unwrap();
+        assert_eq!(headers.get("etag"), Some(&"\"abc\"".to_owned()));
+    }
+
+    #[test]
+    fn head_should_return_updated_response_by_etag() {
+        let url = head_server();
+        let request = WebRequest::create();
+
+        let response = request
+            .head(
+                &format!("{}/downloads/test-file.zip", url),
+                Some("abc"),
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(response, ResponseType::Updated(304));
+    }
+
+    #[rstest]
+    #[case(None, None, None, None)]
+    #[case(Some("abc"), None, Some("\"abc\""), None)]
+    #[case(Some("\"abc\""), None, Some("\"abc\""), None)]
+    #[case(
+        None,
+        Some("Wed, 10 Jun 2020 06:14:18 GMT"),
+        None,
+        Some("Wed, 10 Jun 2020 06:14:18 GMT")
+    )]
+    #[case(
+        Some("abc"),
+        Some("Wed, 10 Jun 2020 06:14:18 GMT"),
+        Some("\"abc\""),
+        Some("Wed, 10 Jun 2020 06:14:18 GMT")
+    )]
+    fn conditional_request_should_set_headers_for_present_fields(
+        #[case] etag: Option<&str>,
+        #[case] last_modified: Option<&str>,
+        #[case] expected_etag: Option<&str>,
+        #[case] expected_last_modified: Option<&str>,
+    ) {
+        let conditional = ConditionalRequest::new(etag, last_modified);
+
+        let headers = conditional.headers(ACCEPTED_TYPES["binary"]).unwrap();
+
+        assert_eq!(
+            headers
+                .get(header::IF_NONE_MATCH)
+                .map(|h| h.to_str().unwrap()),
+            expected_etag
+        );
+        assert_eq!(
+            headers
+                .get(header::IF_MODIFIED_SINCE)
+                .map(|h| h.to_str().unwrap()),
+            expected_last_modified
+        );
+        assert_eq!(
+            headers.get(header::ACCEPT).map(|h| h.to_str().unwrap()),
+            Some(ACCEPTED_TYPES["binary"])
+        );
+    }
+
+    #[test]
+    fn conditional_request_should_be_created_from_response_headers() {
+        let url = head_server();
+        let request = WebRequest::create();
+        let response = match request
+            .head(&format!("{}/test-file.zip", url), None, None)
+            .unwrap()
+        {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+
+        let actual = ConditionalRequest::from_response(&response);
+
+        assert_eq!(
+            actual,
+            ConditionalRequest {
+                etag: Some("\"abc\"".into()),
+                last_modified: None,
+                size: Some(16),
+                range_start: None,
+            }
+        );
+    }
+
+    #[test]
+    fn conditional_request_should_be_created_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ETAG, HeaderValue::from_static("\"abc\""));
+        headers.insert(
+            header::LAST_MODIFIED,
+            HeaderValue::from_static("Wed, 10 Jun 2020 10:00:00 GMT"),
+        );
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("16"));
+
+        let actual = ConditionalRequest::from_headers(&headers);
+
+        assert_eq!(
+            actual,
+            ConditionalRequest {
+                etag: Some("\"abc\"".into()),
+                last_modified: Some("Wed, 10 Jun 2020 10:00:00 GMT".into()),
+                size: Some(16),
+                range_start: None,
+            }
+        );
+    }
+
+    #[test]
+    fn get_conditional_binary_response_should_return_updated_response() {
+        let url = test_server::serve(|request| {
+            if request.header("if-none-match") == Some("\"abc\"") {
+                test_server::response(304, &[], b"")
+            } else {
+                test_server::response(200, &[("ETag", "\"abc\"")], b"content")
+            }
+        });
+        let request = WebRequest::create();
+        let conditional = ConditionalRequest {
+            etag: Some("abc".into()),
+            ..Default::default()
+        };
+
+        let response = request
+            .get_conditional_binary_response(&format!("{}/test.bin", url), &conditional)
+            .unwrap();
+
+        assert_eq!(response, ResponseType::Updated(304));
+    }
+
+    #[test]
+    fn get_binary_response_with_retry_should_retry_server_errors() {
+        let url = test_server::serve(|request| match request.index {
+            0 => test_server::response(503, &[], b"Unavailable"),
+            _ => test_server::response(200, &[], b"Downloaded content"),
+        });
+        let request = WebRequest::create();
+        let work_dir = std::env::temp_dir();
+
+        let mut response = match request
+            .get_binary_response_with_retry(
+                &format!("{}/request-retry.bin", url),
+                &ConditionalRequest::default(),
+                2,
+                Duration::from_millis(10),
+            )
+            .unwrap()
+        {
+            ResponseType::New(response, 200) => response,
+            _ => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&work_dir);
+        let path = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"Downloaded content");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn get_binary_response_with_retry_should_return_last_error_when_all_attempts_fail() {
+        let url = test_server::serve(|_| test_server::response(503, &[], b"Unavailable"));
+        let request = WebRequest::create();
+
+        let err = request
+            .get_binary_response_with_retry(
+                &format!("{}/request-retry-fail.bin", url),
+                &ConditionalRequest::default(),
+                3,
+                Duration::from_millis(10),
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, WebError::HttpStatus { code: 503, .. }));
+    }
+
+    #[test]
+    fn get_binary_response_with_retry_should_not_retry_client_errors() {
+        let url = test_server::serve(|request| match request.index {
+            0 => test_server::response(404, &[], b""),
+            _ => test_server::response(200, &[], b"content"),
+        });
+        let request = WebRequest::create();
+
+        let err = request
+            .get_binary_response_with_retry(
+                &format!("{}/request-retry-missing.bin", url),
+                &ConditionalRequest::default(),
+                3,
+                Duration::from_millis(10),
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, WebError::HttpStatus { code: 404, .. }));
+    }
+
+    #[test]
+    fn is_transient_error_should_be_true_for_connection_failures() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let err = reqwest::blocking::get(&url).unwrap_err();
+
+        assert!(is_transient_error(&WebError::Request(err)));
+    }
+
+    #[test]
+    fn is_transient_error_should_be_false_for_redirect_errors() {
+        let url = test_server::serve(|request| {
+            test_server::response(302, &[("Location", &request.path)], b"")
+        });
+
+        let err = reqwest::blocking::get(&format!("{}/redirect-loop", url)).unwrap_err();
+
+        assert!(err.is_redirect());
+        assert!(!is_transient_error(&WebError::Request(err)));
+    }
+
+    #[test]
+    fn get_binary_response_with_retry_should_reject_zero_attempts() {
+        let url = test_server::serve(|_| test_server::response(200, &[], b"content"));
+        let request = WebRequest::create();
+
+        let err = request
+            .get_binary_response_with_retry(
+                &format!("{}/request-retry-zero.bin", url),
+                &ConditionalRequest::default(),
+                0,
+                Duration::from_millis(10),
+            )
+            .unwrap_err();
+
+        assert!(matches!(err, WebError::Other(_)));
+    }
+
+    fn auth_server(expected: &'static str) -> String {
+        test_server::serve(move |request| {
+            if request.header("authorization") == Some(expected) {
+                test_server::response(200, &[("Content-Type", "text/html")], b"<html></html>")
+            } else {
+                test_server::response(401, &[], b"")
+            }
+        })
+    }
+
+    #[rstest]
+    #[case(WebRequest::create().basic_auth("user", Some("pass")), "Basic dXNlcjpwYXNz")]
+    #[case(WebRequest::create().basic_auth("user", None), "Basic dXNlcjo=")]
+    #[case(WebRequest::create().bearer_token("token123"), "Bearer token123")]
+    fn get_html_response_should_send_credentials(
+        #[case] request: WebRequest,
+        #[case] expected: &'static str,
+    ) {
+        let url = auth_server(expected);
+
+        let response = request.get_html_response(&url);
+
+        assert!(response.is_ok());
+    }
+
+    #[test]
+    fn get_binary_response_should_send_credentials() {
+        let url = auth_server("Bearer token123");
+        let request = WebRequest::create()
+            .bearer_token("token123")
+            .with_decompression(false);
+
+        let response = request
+            .get_binary_response(&format!("{}/test.bin", url), None, None)
+            .unwrap();
+
+        assert!(matches!(response, ResponseType::New(_, 200)));
+    }
+
+    #[test]
+    fn get_html_response_should_return_error_without_credentials() {
+        let url = auth_server("Bearer token123");
+        let request = WebRequest::create();
+
+        let err = request.get_html_response(&url).unwrap_err();
+
+        assert!(matches!(err, WebError::HttpStatus { code: 401, .. }));
+    }
+
+    #[test]
+    fn get_checksums_should_download_and_parse_checksum_file() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[("Content-Type", "text/plain")],
+                b"abcdef0123456789  test-x64.zip\nABCDEF0123456789 *test-x86.exe\n",
+            )
+        });
+        let request = WebRequest::create();
+
+        let checksums = request
+            .get_checksums(&format!("{}/SHA256SUMS", url))
+            .unwrap();
+
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums["test-x64.zip"], "abcdef0123456789");
+        assert_eq!(checksums["test-x86.exe"], "abcdef0123456789");
+    }
+
+    #[test]
+    fn get_checksums_should_return_error_on_missing_file() {
+        let url = test_server::serve(|_| test_server::response(404, &[], b"Not Found"));
+        let request = WebRequest::create();
+
+        let err = request
+            .get_checksums(&format!("{}/SHA256SUMS", url))
+            .unwrap_err();
+
+        match err {
+            WebError::HttpStatus { code, .. } => assert_eq!(code, 404),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+
     #[test]
     fn get_html_response_should_follow_redirection() {
         let final_url =
@@ -6,16 +6,32 @@
 
 /// Contains code related to handling binary responses (normally downloading).
 mod binary;
+/// Contains code related to handling responses without any content (`HEAD`
+/// requests).
+#[cfg(feature = "blocking")]
+mod head;
 /// Contains code related to handling html responses.
 mod html;
 
 use std::collections::HashMap;
+#[cfg(feature = "blocking")]
 use std::path::Path;
 
+#[cfg(feature = "blocking")]
 pub use binary::BinaryResponse;
+#[cfg(feature = "async")]
+pub(crate) use binary::{check_partial_file, finish_download, get_from_disposition, get_from_url};
+#[cfg(feature = "blocking")]
+pub use head::HeadResponse;
+pub use html::parse_version;
+#[cfg(feature = "blocking")]
 pub use html::HtmlResponse;
+#[cfg(feature = "async")]
+pub(crate) use html::{compile_regex, decode_body, parent_link_element, parse_html};
 use lazy_static::lazy_static;
+#[cfg(feature = "blocking")]
 use reqwest::blocking::Response;
+#[cfg(feature = "blocking")]
 use reqwest::StatusCode;
 
 use crate::elements::LinkType;
@@ -43,7 +59,7 @@ lazy_static! {
 /// - Calling any child response may panic if a function is called, and the
 ///   server returned an not modified response.
 #[derive(Debug, PartialEq)]
-pub enum ResponseType<T: WebResponse> {
+pub enum ResponseType<T> {
     /// The response returned by the server was considered up to date, and no
     /// further processing is available. Sets the server status code as a
     /// member.
@@ -56,6 +72,7 @@ pub enum ResponseType<T: WebResponse> {
 }
 
 /// Implements common functions that are also implemented on any child response.
+#[cfg(feature = "blocking")]
 impl<T: WebResponse> ResponseType<T> {
     /// Calls the read function on the underlying web response.
     ///
@@ -75,6 +92,7 @@ impl<T: WebResponse> ResponseType<T> {
 
 /// Implements functions that only makes sense to be called when the response
 /// type is a binary response.
+#[cfg(feature = "blocking")]
 impl ResponseType<BinaryResponse> {
     /// Sets the directory that should be used when calling the child response.
     /// This function should not panic even if the response is considered up to
@@ -97,6 +115,9 @@ impl ResponseType<BinaryResponse> {
 ///   generally for aquiring links on a web page_.
 /// - [BinaryResponse](BinaryResponse): _Responsible for downloading a remote
 ///   file to a specified location_
+/// - [HeadResponse](HeadResponse): _Responsible for reading the headers of a
+///   remote file without downloading it_
+#[cfg(feature = "blocking")]
 pub trait WebResponse {
     /// The response content that will be returned by any implementation of
     /// [WebResponse]. This can be anything that would be expected by the
@@ -123,6 +144,24 @@ pub trait WebResponse {
         headers
     }
 
+    /// Returns the value of a single header that was returned by the web
+    /// server, or `None` if the header was not returned. The name of the
+    /// header is matched case insensitively.
+    fn get_header(&self, name: &str) -> Option<String> {
+        self.get_headers()
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, val)| val.to_string())
+    }
+
+    /// Returns the length of the content as reported by the `Content-Length`
+    /// header returned by the web server, or `None` if the header was not
+    /// returned or is not a valid number.
+    fn content_length(&self) -> Option<u64> {
+        self.get_header(reqwest::header::CONTENT_LENGTH.as_str())
+            .and_then(|len| len.parse().ok())
+    }
+
     /// Returns the status that was returned with the rest of the response.
     fn status(&self) -> StatusCode {
         self.response().status()
@@ -135,9 +174,10 @@ pub trait WebResponse {
     fn read(self, re: Option<&str>) -> Result<Self::ResponseContent, WebError>;
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use reqwest::blocking::get;
+    use rstest::rstest;
 
     use super::*;
 
@@ -166,6 +206,91 @@ mod tests {
         }
     }
 
+    struct HeaderResponse {
+        headers: Vec<(&'static str, &'static str)>,
+    }
+
+    impl WebResponse for HeaderResponse {
+        type ResponseContent = String;
+
+        fn response(&self) -> &reqwest::blocking::Response {
+            unimplemented!()
+        }
+
+        fn get_headers(&self) -> HashMap<&str, &str> {
+            self.headers.iter().cloned().collect()
+        }
+
+        fn read(
+            self,
+            _: Option<&str>,
+        ) -> std::result::Result<<Self as WebResponse>::ResponseContent, WebError> {
+            unimplemented!()
+        }
+    }
+
+    #[test]
+    fn get_header_should_return_value_of_existing_header() {
+        let response = HeaderResponse {
+            headers: vec![("etag", "\"abc\""), ("last-modified", "Wed, 10 Jun 2020")],
+        };
+
+        assert_eq!(response.get_header("etag"), Some("\"abc\"".into()));
+        assert_eq!(
+            response.get_header("last-modified"),
+            Some("Wed, 10 Jun 2020".into())
+        );
+    }
+
+    #[rstest(name, case("etag"), case("ETag"), case("ETAG"))]
+    fn get_header_should_match_header_names_case_insensitively(name: &str) {
+        let response = HeaderResponse {
+            headers: vec![("ETag", "\"abc\""), ("Last-Modified", "Wed, 10 Jun 2020")],
+        };
+
+        assert_eq!(response.get_header(name), Some("\"abc\"".into()));
+        assert_eq!(
+            response.get_header("last-modified"),
+            Some("Wed, 10 Jun 2020".into())
+        );
+    }
+
+    #[test]
+    fn content_length_should_match_mixed_case_header() {
+        let response = HeaderResponse {
+            headers: vec![("Content-Length", "1024")],
+        };
+
+        assert_eq!(response.content_length(), Some(1024));
+    }
+
+    #[test]
+    fn get_header_should_return_none_on_missing_header() {
+        let response = HeaderResponse {
+            headers: vec![("etag", "\"abc\"")],
+        };
+
+        assert_eq!(response.get_header("content-type"), None);
+    }
+
+    #[test]
+    fn content_length_should_return_parsed_length() {
+        let response = HeaderResponse {
+            headers: vec![("content-length", "16376743")],
+        };
+
+        assert_eq!(response.content_length(), Some(16376743));
+    }
+
+    #[rstest(headers, case(vec![]), case(vec![("content-length", "invalid")]))]
+    fn content_length_should_return_none_on_missing_or_invalid_header(
+        headers: Vec<(&'static str, &'static str)>,
+    ) {
+        let response = HeaderResponse { headers };
+
+        assert_eq!(response.content_length(), None);
+    }
+
     #[test]
     fn status_should_get_the_actual_status_code_of_response() {
         let response = get("https://httpbin.org/status/406").unwrap();
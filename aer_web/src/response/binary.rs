@@ -1,17 +1,27 @@
 // Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
 // Licensed under the MIT license. See LICENSE.txt file in the project
 
-use std::fs::File;
-use std::io::BufWriter;
+#[cfg(feature = "blocking")]
+use std::fs::OpenOptions;
+#[cfg(feature = "blocking")]
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+#[cfg(feature = "blocking")]
+use std::time::Duration;
 
 use log::{info, warn};
-use reqwest::blocking::Response;
+#[cfg(feature = "blocking")]
+use reqwest::blocking::{RequestBuilder, Response};
 use reqwest::header::{HeaderMap, HeaderValue};
+#[cfg(feature = "blocking")]
+use reqwest::StatusCode;
 use reqwest::{header, Url};
 
+#[cfg(feature = "blocking")]
+use crate::request::{check_attempts, check_status, is_transient_error};
 use crate::response::WebError;
+#[cfg(feature = "blocking")]
 use crate::WebResponse;
 
 /// Contains functions and items necessary for parsing and downloading binary
@@ -19,19 +29,25 @@ use crate::WebResponse;
 ///
 /// Implements the [WebResponse] trait, and are not meant to be created directly
 /// by a user.
+#[cfg(feature = "blocking")]
 #[derive(Debug)]
 pub struct BinaryResponse {
     response: Response,
     url: Url,
     work_dir: PathBuf,
+    request: Option<RequestBuilder>,
+    range_start: Option<u64>,
+    source: Option<PathBuf>,
 }
 
+#[cfg(feature = "blocking")]
 impl PartialEq for BinaryResponse {
     fn eq(&self, rhs: &BinaryResponse) -> bool {
         self.work_dir == rhs.work_dir // We do not compare the actual response, as it is not interesting
     }
 }
 
+#[cfg(feature = "blocking")]
 impl BinaryResponse {
     /// Creates a new instance of the [BinaryResponse] structure to hold the
     /// current response, and allow downloading the remote file from the content
@@ -41,9 +57,54 @@ impl BinaryResponse {
             response,
             url,
             work_dir: PathBuf::new(),
+            request: None,
+            range_start: None,
+            source: None,
         }
     }
 
+    /// Creates a new instance of the [BinaryResponse] structure for a local
+    /// `file://` url, the local file is copied to the output file when the
+    /// response is read. The response will only contain the `Content-Length`
+    /// header of the local file.
+    pub(crate) fn from_file(url: Url) -> Result<BinaryResponse, WebError> {
+        let source = url
+            .to_file_path()
+            .map_err(|_| WebError::Other(format!("The url '{}' is not a local file path", url)))?;
+        let metadata = source.metadata()?;
+        if !metadata.is_file() {
+            return Err(WebError::Other(format!(
+                "The path '{}' is not a file",
+                source.display()
+            )));
+        }
+
+        let response = http::Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, metadata.len())
+            .body(Vec::new())
+            .map_err(|err| WebError::Other(err.to_string()))?;
+        let mut response = BinaryResponse::new(Response::from(response), url);
+        response.source = Some(source);
+
+        Ok(response)
+    }
+
+    /// Associates the request that was used to create the response, this
+    /// allows the request to be sent again when a download needs to be
+    /// retried.
+    pub(crate) fn with_request(mut self, request: Option<RequestBuilder>) -> BinaryResponse {
+        self.request = request;
+        self
+    }
+
+    /// Sets the position the partial content of the response starts at, the
+    /// content will then be appended to the existing partial file.
+    pub(crate) fn with_range_start(mut self, range_start: Option<u64>) -> BinaryResponse {
+        self.range_start = range_start;
+        self
+    }
+
     /// Sets the current work directory (the directory where files will be
     /// downloaded). If this function is never called, the current directory
     /// (based on the execution location of the program) will be used. As such,
@@ -55,17 +116,274 @@ impl BinaryResponse {
     /// Tries to get the name of the remote file by either reading the
     /// disposition header, or checking the url if it contains an extension.
     pub fn file_name(&self) -> Option<String> {
+        // Responses of local files are not created from a request, and do not
+        // hold the actual url
+        let url = if self.source.is_some() {
+            &self.url
+        } else {
+            self.response.url()
+        };
+
         if let Some(name) = get_from_disposition(self.response.headers()) {
             Some(name)
-        } else if let Some(name) = get_from_url(self.response.url()) {
+        } else if let Some(name) = get_from_url(url) {
             Some(name)
         } else {
             None
         }
     }
+
+    /// Reads and downloads the response content the same way as
+    /// [read](BinaryResponse::read), but will retry the download if a network
+    /// error occurs or the server responds with a server error (`5xx`).
+    ///
+    /// ## Arguments
+    ///
+    /// - `output`: The name of the file to create, if not specified it will be
+    ///   resolved from the response.
+    /// - `attempts`: The maximum number of times the download will be
+    ///   attempted, including the first attempt. Must be at least `1`.
+    /// - `backoff`: The time to wait before the first retry, the time will be
+    ///   doubled for every following retry.
+    ///
+    /// ## Returns
+    ///
+    /// On a successful download, the written path will be returned. If all of
+    /// the attempts fail, the error of the last attempt is returned. An error
+    /// is returned without reading the response if `attempts` is `0`.
+    ///
+    /// ## Notes
+    ///
+    /// - Any `etag` or `last_modified` value that was used when creating the
+    ///   response will be sent along with every retried request.
+    /// - Only failures after the response was created are retried here, use
+    ///   [get_binary_response_with_retry](crate::WebRequest::get_binary_response_with_retry)
+    ///   to also retry failures of the initial request.
+    pub fn read_with_retry(
+        self,
+        output: Option<&str>,
+        attempts: u32,
+        backoff: Duration,
+    ) -> Result<PathBuf, WebError> {
+        check_attempts(attempts)?;
+        let output = self.output_path(output)?;
+        if let Some(source) = &self.source {
+            return copy_file(source, &output);
+        }
+        let mut result = write_response(self.response, &self.url, &output, self.range_start);
+        let mut attempt = 1;
+        let mut wait = backoff;
+
+        while attempt < attempts && is_transient(&result) {
+            let request = match self.request.as_ref().and_then(|r| r.try_clone()) {
+                Some(request) => request,
+                None => break,
+            };
+            attempt += 1;
+
+            warn!(
+                "Download of '{}' failed, retrying in {:?} (attempt {} of {})",
+                self.url, wait, attempt, attempts
+            );
+            std::thread::sleep(wait);
+            wait *= 2;
+
+            result = match request.send() {
+                Ok(response) => check_status(response)
+                    .and_then(|response| download(response, &self.url, &output, false)),
+                Err(err) => Err(WebError::Request(err)),
+            };
+        }
+
+        result
+    }
+
+    fn output_path(&self, output: Option<&str>) -> Result<PathBuf, WebError> {
+        let output = if let Some(output) = output {
+            output.into()
+        } else {
+            self.file_name()
+                .ok_or_else(|| WebError::Other("Unable to extract file name request".into()))?
+        };
+
+        Ok(self.work_dir.join(output))
+    }
+}
+
+/// Writes the response to the output file, appending the content to the
+/// partial file when the response only contains the remaining content.
+#[cfg(feature = "blocking")]
+fn write_response(
+    response: Response,
+    url: &Url,
+    output: &Path,
+    range_start: Option<u64>,
+) -> Result<PathBuf, WebError> {
+    match range_start {
+        Some(start) => {
+            check_partial_file(output, start)?;
+            download(response, url, output, true)
+        }
+        None => download(response, url, output, false),
+    }
+}
+
+/// Copies the local file of a `file://` url to the output file.
+#[cfg(feature = "blocking")]
+fn copy_file(source: &Path, output: &Path) -> Result<PathBuf, WebError> {
+    info!("Copying '{}' to '{}'", source.display(), output.display());
+
+    // Copying a file onto itself would truncate it
+    let same_file = match (source.canonicalize(), output.canonicalize()) {
+        (Ok(source), Ok(output)) => source == output,
+        _ => false,
+    };
+    if !same_file {
+        std::fs::copy(source, output)?;
+    }
+
+    info!("Successfully copied '{}'", output.display());
+    Ok(output.to_path_buf())
 }
 
-fn get_from_url(url: &Url) -> Option<String> {
+/// Verifies that the partial file contains exactly the content before the
+/// specified start of the remaining content. Shared by both the blocking and
+/// asynchronous downloads.
+pub(crate) fn check_partial_file(output: &Path, start: u64) -> Result<(), WebError> {
+    let existing_len = output.metadata().map(|m| m.len()).unwrap_or(0);
+
+    if existing_len == start {
+        Ok(())
+    } else {
+        Err(WebError::Other(format!(
+            "The partial file '{}' contains {} bytes, but the response continues from byte {}",
+            output.display(),
+            existing_len,
+            start
+        )))
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn is_transient(result: &Result<PathBuf, WebError>) -> bool {
+    result.as_ref().err().map_or(false, is_transient_error)
+}
+
+/// Simple writer wrapper that keeps track of how many bytes have been written
+/// to the underlying writer.
+#[cfg(feature = "blocking")]
+struct CountingWriter<W: Write> {
+    inner: W,
+    written: u64,
+}
+
+#[cfg(feature = "blocking")]
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        self.written += size as u64;
+
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn download(
+    mut response: Response,
+    url: &Url,
+    output: &Path,
+    append: bool,
+) -> Result<PathBuf, WebError> {
+    info!("Downloading '{}' to '{}'", url, output.display());
+
+    let expected = response.content_length();
+    let (result, written) = {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(output)
+            .map_err(WebError::IoError)?;
+        let mut writer = CountingWriter {
+            inner: BufWriter::new(file),
+            written: 0,
+        };
+
+        let result = match response.copy_to(&mut writer) {
+            Ok(_) => writer.flush().map_err(WebError::IoError),
+            Err(err) => Err(WebError::Request(err)),
+        };
+
+        (result, writer.written)
+    };
+
+    finish_download(url, output, result, expected, written, append)
+}
+
+/// Finishes a download by returning the error of a failed copy, or by
+/// verifying the written length of a successful copy. The written file is
+/// removed on a failure, unless it is a partial file being appended to (*to
+/// allow the download to be resumed again*). Shared by both the blocking and
+/// asynchronous downloads.
+pub(crate) fn finish_download(
+    url: &Url,
+    output: &Path,
+    result: Result<(), WebError>,
+    expected: Option<u64>,
+    written: u64,
+    append: bool,
+) -> Result<PathBuf, WebError> {
+    if let Err(err) = result {
+        warn!("Failed to download '{}'", url);
+        if !append {
+            let _ = std::fs::remove_file(output);
+        }
+
+        return Err(err);
+    }
+
+    check_length(url, output, expected, written, append)?;
+
+    info!("Successfully downloaded '{}'", output.display());
+    Ok(output.to_path_buf())
+}
+
+/// Verifies that the amount of bytes written matches the length advertised by
+/// the server, the written file is removed on a mismatch unless the partial
+/// content should be kept.
+fn check_length(
+    url: &Url,
+    output: &Path,
+    expected: Option<u64>,
+    written: u64,
+    keep_partial: bool,
+) -> Result<(), WebError> {
+    if let Some(expected) = expected {
+        if written != expected {
+            warn!(
+                "Failed to download '{}', expected {} bytes but only {} bytes was received",
+                url, expected, written
+            );
+            if !keep_partial {
+                let _ = std::fs::remove_file(output);
+            }
+
+            return Err(WebError::IncompleteDownload {
+                expected,
+                actual: written,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn get_from_url(url: &Url) -> Option<String> {
     let segments = url.path_segments()?;
     let mut extension = String::new();
 
@@ -94,7 +412,7 @@ fn get_from_url(url: &Url) -> Option<String> {
     }
 }
 
-fn get_from_disposition(headers: &HeaderMap<HeaderValue>) -> Option<String> {
+pub(crate) fn get_from_disposition(headers: &HeaderMap<HeaderValue>) -> Option<String> {
     if let Some(disposition) = headers
         .get(header::CONTENT_DISPOSITION)
         .and_then(|d| d.to_str().ok())
@@ -118,6 +436,7 @@ fn get_from_disposition(headers: &HeaderMap<HeaderValue>) -> Option<String> {
     None
 }
 
+#[cfg(feature = "blocking")]
 impl WebResponse for BinaryResponse {
     /// The path to a written file.
     type ResponseContent = PathBuf;
@@ -128,6 +447,14 @@ impl WebResponse for BinaryResponse {
 
     /// Reads and downloads the response content.
     ///
+    /// When the response was created for a local `file://` url, the local file
+    /// is copied to the output file instead.
+    ///
+    /// When the response was created with a partial file (see
+    /// [ConditionalRequest::with_partial_file](crate::ConditionalRequest::with_partial_file))
+    /// and the server responded with `206 Partial Content`, the remaining
+    /// content is appended to the partial file instead.
+    ///
     /// ## Arguments
     ///
     /// - `output`: The name of the file to create, if not specified it will be
@@ -135,49 +462,38 @@ impl WebResponse for BinaryResponse {
     ///
     /// ## Returns
     ///
-    /// On a successful download, the written path will be returned.
+    /// On a successful download, the written path will be returned. If the
+    /// server advertised the length of the file, and the written file do not
+    /// match this length the partial file is removed and an
+    /// [IncompleteDownload](WebError::IncompleteDownload) error is returned.
+    /// Any failure while copying the content (*ie: the connection being closed
+    /// or the disk being full*) is returned before the length is compared.
     ///
     /// ## Warning
     ///
     /// The `output` argument will be combined with the previously set work
     /// directory.
     fn read(self, output: Option<&str>) -> Result<Self::ResponseContent, WebError> {
-        let output = if let Some(output) = output {
-            output.into()
-        } else {
-            self.file_name()
-                .ok_or_else(|| WebError::Other("Unable to extract file name request".into()))?
-        };
-
-        let output = self.work_dir.join(output);
-
-        let mut response = self.response;
-
-        info!("Downloading '{}' to '{}'", self.url, output.display());
-
-        let file = File::create(output.clone()).map_err(WebError::IoError)?;
-        let mut writer = BufWriter::new(&file);
-
-        match response.copy_to(&mut writer) {
-            Err(err) => {
-                warn!("Failed to download '{}'", self.url);
-                Err(WebError::Request(err))
-            }
-            Ok(_) => {
-                info!("Successfully downloaded '{}'", output.display());
-                Ok(output)
-            }
+        let output = self.output_path(output)?;
+        if let Some(source) = &self.source {
+            return copy_file(source, &output);
         }
+
+        write_response(self.response, &self.url, &output, self.range_start)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
     use reqwest::{header, Url};
     use rstest::rstest;
 
     use super::*;
-    use crate::WebRequest;
+    use crate::response::ResponseType;
+    use crate::{test_server, ConditionalRequest, WebRequest};
 
     #[rstest(
         test,
@@ -275,4 +591,455 @@ mod tests {
 
         let _ = std::fs::remove_file(expected);
     }
+
+    #[test]
+    fn read_with_retry_should_download_file_after_failed_attempts() {
+        let url = test_server::serve(|request| match request.index {
+            0 => test_server::truncated_response(b"Partial", 1024),
+            1 => test_server::response(500, &[], b"Server Error"),
+            _ => test_server::response(200, &[], b"Downloaded content"),
+        });
+        let work_dir = std::env::temp_dir();
+        let request = WebRequest::create();
+        let response = request
+            .get_binary_response(&format!("{}/retry-test.bin", url), None, None)
+            .unwrap();
+        let mut response = match response {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&work_dir);
+
+        let path = response
+            .read_with_retry(None, 3, Duration::from_millis(10))
+            .unwrap();
+
+        assert_eq!(path, work_dir.join("retry-test.bin"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"Downloaded content");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn read_with_retry_should_return_last_error_when_all_attempts_fail() {
+        let url = test_server::serve(|request| match request.index {
+            0 => test_server::truncated_response(b"Partial", 1024),
+            _ => test_server::response(503, &[], b"Unavailable"),
+        });
+        let request = WebRequest::create();
+        let response = request
+            .get_binary_response(&format!("{}/retry-fail.bin", url), None, None)
+            .unwrap();
+        let mut response = match response {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&std::env::temp_dir());
+
+        let err = response
+            .read_with_retry(None, 2, Duration::from_millis(10))
+            .unwrap_err();
+
+        match err {
+            WebError::HttpStatus { code, .. } => assert_eq!(code, 503),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+
+        let _ = std::fs::remove_file(std::env::temp_dir().join("retry-fail.bin"));
+    }
+
+    #[test]
+    fn read_with_retry_should_send_etag_and_last_modified_on_retries() {
+        let url = test_server::serve(|request| {
+            let etag = request.header("if-none-match");
+            let modified = request.header("if-modified-since");
+            if etag != Some("\"test-etag\"") || modified != Some("Tue, 16 Feb 2021 03:33:36 GMT") {
+                test_server::response(400, &[], b"Missing conditional headers")
+            } else if request.index == 0 {
+                test_server::truncated_response(b"Partial", 1024)
+            } else {
+                test_server::response(200, &[], b"Downloaded content")
+            }
+        });
+        let request = WebRequest::create();
+        let response = request
+            .get_binary_response(
+                &format!("{}/retry-conditional.bin", url),
+                Some("test-etag"),
+                Some("Tue, 16 Feb 2021 03:33:36 GMT"),
+            )
+            .unwrap();
+        let mut response = match response {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&std::env::temp_dir());
+
+        let path = response
+            .read_with_retry(None, 2, Duration::from_millis(10))
+            .unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"Downloaded content");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn read_with_retry_should_return_error_on_zero_attempts() {
+        let url = test_server::serve(|_| test_server::response(200, &[], b"content"));
+        let request = WebRequest::create();
+        let mut response = match request
+            .get_binary_response(&format!("{}/retry-zero.bin", url), None, None)
+            .unwrap()
+        {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        let work_dir = std::env::temp_dir();
+        response.set_work_dir(&work_dir);
+
+        let err = response
+            .read_with_retry(None, 0, Duration::from_millis(10))
+            .unwrap_err();
+
+        assert!(matches!(err, WebError::Other(_)));
+        assert!(!work_dir.join("retry-zero.bin").exists());
+    }
+
+    #[test]
+    fn read_should_return_copy_error_and_remove_file_on_truncated_download() {
+        let url = test_server::serve(|_| test_server::truncated_response(b"Partial", 1024));
+        let work_dir = std::env::temp_dir();
+        let request = WebRequest::create();
+        let mut response = request
+            .get_binary_response(&format!("{}/incomplete-test.bin", url), None, None)
+            .unwrap();
+        response.set_work_dir(&work_dir);
+
+        let err = response.read(None).unwrap_err();
+
+        match err {
+            WebError::Request(err) => assert!(err.is_body()),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+        assert!(!work_dir.join("incomplete-test.bin").exists());
+    }
+
+    #[test]
+    fn finish_download_should_return_copy_error_before_comparing_length() {
+        let url = Url::parse("https://example.org/disk-full.bin").unwrap();
+        let output = std::env::temp_dir().join("aer-web-disk-full.bin");
+        std::fs::write(&output, b"Par").unwrap();
+        let result = Err(WebError::IoError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "No space left on device",
+        )));
+
+        let err = finish_download(&url, &output, result, Some(10), 3, false).unwrap_err();
+
+        assert!(matches!(err, WebError::IoError(_)));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn finish_download_should_keep_partial_file_on_copy_error_when_appending() {
+        let url = Url::parse("https://example.org/disk-full-append.bin").unwrap();
+        let output = std::env::temp_dir().join("aer-web-disk-full-append.bin");
+        std::fs::write(&output, b"Par").unwrap();
+        let result = Err(WebError::Other("Failed to copy".into()));
+
+        let err = finish_download(&url, &output, result, Some(10), 3, true).unwrap_err();
+
+        assert!(matches!(err, WebError::Other(_)));
+        assert!(output.exists());
+
+        let _ = std::fs::remove_file(output);
+    }
+
+    #[test]
+    fn finish_download_should_return_incomplete_download_after_successful_copy() {
+        let url = Url::parse("https://example.org/short.bin").unwrap();
+        let output = std::env::temp_dir().join("aer-web-short.bin");
+        std::fs::write(&output, b"Par").unwrap();
+
+        let err = finish_download(&url, &output, Ok(()), Some(10), 3, false).unwrap_err();
+
+        match err {
+            WebError::IncompleteDownload { expected, actual } => {
+                assert_eq!(expected, 10);
+                assert_eq!(actual, 3);
+            }
+            err => panic!("Unexpected error returned: {}", err),
+        }
+        assert!(!output.exists());
+    }
+
+    fn fixture_url(name: &str) -> Url {
+        let path = std::env::current_dir()
+            .unwrap()
+            .join("test-data")
+            .join(name);
+
+        Url::from_file_path(path).unwrap()
+    }
+
+    #[test]
+    fn read_should_copy_local_file_url() {
+        let url = fixture_url("file-url-fixture.bin");
+        let work_dir = std::env::temp_dir().join("aer-web-file-url");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let request = WebRequest::create();
+        let mut response = match request
+            .get_binary_response(url.as_str(), None, None)
+            .unwrap()
+        {
+            ResponseType::New(response, status) => {
+                assert_eq!(status, 200);
+                response
+            }
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        response.set_work_dir(&work_dir);
+
+        assert_eq!(response.file_name(), Some("file-url-fixture.bin".into()));
+        assert_eq!(response.content_length(), Some(41));
+
+        let path = response.read(None).unwrap();
+
+        assert_eq!(path, work_dir.join("file-url-fixture.bin"));
+        assert_eq!(
+            std::fs::read(&path).unwrap(),
+            b"Local fixture used by the file url tests\n"
+        );
+
+        let _ = std::fs::remove_dir_all(work_dir);
+    }
+
+    #[test]
+    fn get_binary_response_should_return_error_on_missing_local_file() {
+        let url = fixture_url("non-existing.bin");
+        let request = WebRequest::create();
+
+        let err = request
+            .get_binary_response(url.as_str(), None, None)
+            .unwrap_err();
+
+        match err {
+            WebError::IoError(err) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+
+    #[test]
+    fn read_should_download_file_without_advertised_length() {
+        let url = test_server::serve(|_| {
+            b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nContent without length".to_vec()
+        });
+        let work_dir = std::env::temp_dir();
+        let request = WebRequest::create();
+        let mut response = request
+            .get_binary_response(&format!("{}/no-length-test.bin", url), None, None)
+            .unwrap();
+        response.set_work_dir(&work_dir);
+
+        let path = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"Content without length");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn range_server() -> String {
+        test_server::serve(|request| {
+            const CONTENT: &[u8] = b"0123456789abcdef";
+            match (request.header("range"), request.header("if-range")) {
+                (Some("bytes=6-"), Some("\"v1\"")) => {
+                    test_server::response(206, &[("Content-Range", "bytes 6-15/16")], &CONTENT[6..])
+                }
+                (Some(_), Some("\"v1\"")) => {
+                    test_server::response(416, &[("Content-Range", "bytes */16")], b"")
+                }
+                _ => test_server::response(200, &[("ETag", "\"v1\"")], CONTENT),
+            }
+        })
+    }
+
+    fn get_partial_response(url: &str, path: &Path) -> ResponseType<BinaryResponse> {
+        let request = WebRequest::create();
+        let conditional = ConditionalRequest::new(Some("v1"), None).with_partial_file(path);
+        let mut response = request
+            .get_conditional_binary_response(url, &conditional)
+            .unwrap();
+        response.set_work_dir(path.parent().unwrap());
+
+        response
+    }
+
+    #[test]
+    fn read_should_append_remaining_content_to_partial_file() {
+        let url = range_server();
+        let path = std::env::temp_dir().join("resume-partial-test.bin");
+        std::fs::write(&path, b"012345").unwrap();
+        let response = get_partial_response(&format!("{}/resume-partial-test.bin", url), &path);
+
+        let result = response.read(None).unwrap();
+
+        assert_eq!(result, path);
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn read_should_download_full_file_when_server_ignores_range() {
+        let url = test_server::serve(|_| test_server::response(200, &[], b"0123456789abcdef"));
+        let path = std::env::temp_dir().join("resume-no-range-test.bin");
+        std::fs::write(&path, b"012345").unwrap();
+        let response = get_partial_response(&format!("{}/resume-no-range-test.bin", url), &path);
+
+        let result = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&result).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn read_should_download_full_file_when_no_partial_file_exist() {
+        let url = test_server::serve(|request| {
+            if request.header("range").is_some() {
+                test_server::response(400, &[], b"Unexpected range")
+            } else {
+                test_server::response(200, &[], b"0123456789abcdef")
+            }
+        });
+        let path = std::env::temp_dir().join("resume-new-test.bin");
+        let _ = std::fs::remove_file(&path);
+        let response = get_partial_response(&format!("{}/resume-new-test.bin", url), &path);
+
+        let result = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&result).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn read_should_download_full_file_when_remote_file_changed() {
+        let url = test_server::serve(|request| match request.index {
+            0 => test_server::response(200, &[("ETag", "\"v1\"")], b"0123456789abcdef"),
+            _ => match (request.header("range"), request.header("if-range")) {
+                (Some("bytes=6-"), Some("\"v2\"")) => {
+                    test_server::response(206, &[("Content-Range", "bytes 6-15/16")], b"GHIJKLMNOP")
+                }
+                _ => test_server::response(200, &[("ETag", "\"v2\"")], b"ABCDEFGHIJKLMNOP"),
+            },
+        });
+        let url = format!("{}/resume-changed-test.bin", url);
+        let path = std::env::temp_dir().join("resume-changed-test.bin");
+        let request = WebRequest::create();
+        let mut response = match request.get_binary_response(&url, None, None).unwrap() {
+            ResponseType::New(response, _) => response,
+            ResponseType::Updated(_) => panic!("Expected a new response!"),
+        };
+        let conditional = ConditionalRequest::from_response(&response);
+        response.set_work_dir(path.parent().unwrap());
+        response.read(None).unwrap();
+        // Simulates an interrupted download of the first version
+        std::fs::write(&path, b"012345").unwrap();
+        let conditional = conditional.with_partial_file(&path);
+
+        let mut response = request
+            .get_conditional_binary_response(&url, &conditional)
+            .unwrap();
+        response.set_work_dir(path.parent().unwrap());
+        let result = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&result).unwrap(), b"ABCDEFGHIJKLMNOP");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn get_conditional_binary_response_should_not_request_range_without_validator() {
+        let url = test_server::serve(|request| {
+            if request.header("range").is_some() {
+                test_server::response(400, &[], b"Unexpected range")
+            } else {
+                test_server::response(200, &[], b"0123456789abcdef")
+            }
+        });
+        let path = std::env::temp_dir().join("resume-no-validator-test.bin");
+        std::fs::write(&path, b"012345").unwrap();
+        let request = WebRequest::create();
+        let conditional = ConditionalRequest::default().with_partial_file(&path);
+
+        let mut response = request
+            .get_conditional_binary_response(
+                &format!("{}/resume-no-validator-test.bin", url),
+                &conditional,
+            )
+            .unwrap();
+        response.set_work_dir(path.parent().unwrap());
+        let result = response.read(None).unwrap();
+
+        assert_eq!(std::fs::read(&result).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn get_conditional_binary_response_should_send_single_ranged_request() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&requests);
+        let url = test_server::serve(move |request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            match request.header("range") {
+                Some("bytes=6-") => {
+                    test_server::response(206, &[("Content-Range", "bytes 6-15/16")], b"6789abcdef")
+                }
+                _ => test_server::response(400, &[], b"Expected a range"),
+            }
+        });
+        let path = std::env::temp_dir().join("resume-single-request-test.bin");
+        std::fs::write(&path, b"012345").unwrap();
+        let response =
+            get_partial_response(&format!("{}/resume-single-request-test.bin", url), &path);
+
+        response.read(None).unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn get_conditional_binary_response_should_treat_complete_partial_file_as_updated() {
+        let url = range_server();
+        let path = std::env::temp_dir().join("resume-complete-test.bin");
+        std::fs::write(&path, b"0123456789abcdef").unwrap();
+
+        let response = get_partial_response(&format!("{}/resume-complete-test.bin", url), &path);
+
+        assert_eq!(response, ResponseType::Updated(416));
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn get_conditional_binary_response_should_download_full_file_on_oversized_partial_file() {
+        let url = range_server();
+        let path = std::env::temp_dir().join("resume-oversized-test.bin");
+        std::fs::write(&path, b"0123456789abcdef-extra").unwrap();
+        let response = get_partial_response(&format!("{}/resume-oversized-test.bin", url), &path);
+
+        let result = response.read(None).unwrap();
+
+        assert_eq!(result, path);
+        assert_eq!(std::fs::read(&path).unwrap(), b"0123456789abcdef");
+
+        let _ = std::fs::remove_file(path);
+    }
 }
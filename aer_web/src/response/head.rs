@@ -0,0 +1,60 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+use std::collections::HashMap;
+
+use reqwest::blocking::Response;
+
+use super::binary::{get_from_disposition, get_from_url};
+use crate::response::WebError;
+use crate::WebResponse;
+
+/// Contains the response of a `HEAD` request, allowing the headers of a remote
+/// file to be inspected without downloading the actual file.
+///
+/// Implements the [WebResponse] trait, and are not meant to be created directly
+/// by a user.
+#[derive(Debug)]
+pub struct HeadResponse {
+    response: Response,
+}
+
+impl HeadResponse {
+    /// Creates a new instance of the [HeadResponse] structure to hold the
+    /// current response.
+    pub fn new(response: Response) -> HeadResponse {
+        HeadResponse { response }
+    }
+
+    /// Tries to get the name of the remote file by either reading the
+    /// disposition header, or checking the url if it contains an extension.
+    pub fn file_name(&self) -> Option<String> {
+        get_from_disposition(self.response.headers()).or_else(|| get_from_url(self.response.url()))
+    }
+}
+
+impl PartialEq for HeadResponse {
+    fn eq(&self, rhs: &HeadResponse) -> bool {
+        self.response.url() == rhs.response.url()
+    }
+}
+
+impl WebResponse for HeadResponse {
+    /// The headers returned by the server, with the names of the headers in
+    /// lowercase.
+    type ResponseContent = HashMap<String, String>;
+
+    fn response(&self) -> &Response {
+        &self.response
+    }
+
+    /// Reads the headers returned by the server, the specified argument is
+    /// ignored as there is no content to read.
+    fn read(self, _: Option<&str>) -> Result<Self::ResponseContent, WebError> {
+        Ok(self
+            .get_headers()
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect())
+    }
+}
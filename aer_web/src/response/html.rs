@@ -2,33 +2,106 @@
 // Licensed under the MIT license. See LICENSE.txt file in the project
 
 use aer_version::Versions;
+use encoding_rs::{Encoding, UTF_8};
+use lazy_static::lazy_static;
 use regex::{Captures, Regex};
+#[cfg(feature = "blocking")]
 use reqwest::blocking::Response;
 use reqwest::{header, Url};
 use select::document::Document;
 use select::predicate::Name;
 
 use crate::response::{WebError, MIME_TYPES};
-use crate::{LinkElement, LinkType, WebResponse};
+#[cfg(feature = "blocking")]
+use crate::WebResponse;
+use crate::{LinkElement, LinkType, LinkTypeMapping};
+
+lazy_static! {
+    static ref META_CHARSET_RE: Regex =
+        Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?\s*([a-z0-9_\-:.]+)"#).unwrap();
+}
+
+/// The amount of bytes that will be inspected for a `<meta>` element declaring
+/// the charset of the page.
+const META_SNIFF_LENGTH: usize = 1024;
 
 /// Contains functions and structure for holding a single html response, and
 /// extracting any necessary information out of the html page.
 ///
 /// Implements the [WebResponse] trait, and are not meant to be created directly
 /// by a user.
+#[cfg(feature = "blocking")]
 #[derive(Debug)]
 pub struct HtmlResponse {
     response: Response,
+    link_types: LinkTypeMapping,
+    strip_v_prefix: bool,
 }
 
+#[cfg(feature = "blocking")]
 impl HtmlResponse {
     /// Creates a new instance of the [HtmlResponse] structe to hold the current
     /// response, and allow reading the content from that response.
     pub fn new(response: Response) -> HtmlResponse {
-        HtmlResponse { response }
+        HtmlResponse {
+            response,
+            link_types: LinkTypeMapping::default(),
+            strip_v_prefix: true,
+        }
+    }
+
+    /// Sets the mapping that will be used to classify the type of the links
+    /// found when reading the response. Uses the default
+    /// [LinkTypeMapping] if not set.
+    pub fn set_link_types(&mut self, link_types: LinkTypeMapping) {
+        self.link_types = link_types;
+    }
+
+    /// Sets whether a leading `v` or `V` (*ie: `v1.2.3`*) should be removed
+    /// from any captured version before it is parsed. This is enabled by
+    /// default.
+    pub fn set_strip_v_prefix(&mut self, strip_v_prefix: bool) {
+        self.strip_v_prefix = strip_v_prefix;
+    }
+
+    /// Reads the current response the same way as
+    /// [read](HtmlResponse::read), but additionally extracts a version from
+    /// the text of the html page using the specified `version_re` regex.
+    ///
+    /// This is useful for pages where the latest version is only available in
+    /// the text of the page (*ie: in a heading*), while the download links do
+    /// not include the version. The version found in the page text is set on
+    /// the parent link, and on any link where a version could not be
+    /// extracted from the link itself.
+    ///
+    /// ## Arguments
+    ///
+    /// - `re`: The regex used to filter links, and extract the version from the
+    ///   link url.
+    /// - `version_re`: The regex to use on the page text, the version must be
+    ///   captured in a group named `version`.
+    pub fn read_with_version(
+        self,
+        re: Option<&str>,
+        version_re: &str,
+    ) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+        let version_re = compile_regex(version_re)?;
+        let parent_link = get_parent_link_element(&self);
+
+        let body = read_body(self.response)?;
+
+        parse_html(
+            body,
+            parent_link,
+            re,
+            Some(&version_re),
+            &self.link_types,
+            self.strip_v_prefix,
+        )
     }
 }
 
+#[cfg(feature = "blocking")]
 impl WebResponse for HtmlResponse {
     /// Sets the response type that will be returned when calling the
     /// [read](HtmlResponse::read) function. The first item is the link the
@@ -46,17 +119,99 @@ impl WebResponse for HtmlResponse {
     /// response do not have a successful status code, or if the reading of the
     /// body fails.
     fn read(self, re: Option<&str>) -> Result<Self::ResponseContent, WebError> {
-        let response_url = self.response.url().clone();
-
         let parent_link = get_parent_link_element(&self);
 
-        let body = self.response.text().map_err(WebError::Request)?;
-        let links = get_link_elements(body, response_url, re)?;
+        let body = read_body(self.response)?;
 
-        Ok((parent_link, links))
+        parse_html(
+            body,
+            parent_link,
+            re,
+            None,
+            &self.link_types,
+            self.strip_v_prefix,
+        )
     }
 }
 
+/// Extracts the link elements found in the html body, and sets the version
+/// found in the text of the page (*when a `version_re` is specified*) on the
+/// parent link and any link without a version. Shared by both the blocking and
+/// asynchronous html responses.
+pub(crate) fn parse_html(
+    body: String,
+    mut parent_link: LinkElement,
+    re: Option<&str>,
+    version_re: Option<&Regex>,
+    link_types: &LinkTypeMapping,
+    strip_v_prefix: bool,
+) -> Result<(LinkElement, Vec<LinkElement>), WebError> {
+    let version =
+        version_re.and_then(|version_re| get_page_version(&body, version_re, strip_v_prefix));
+    let mut links = get_link_elements(
+        body,
+        parent_link.link.clone(),
+        re,
+        link_types,
+        strip_v_prefix,
+    )?;
+
+    if let Some(version) = version {
+        for link in links.iter_mut().filter(|link| link.version.is_none()) {
+            link.version = Some(version.clone());
+        }
+        parent_link.version = Some(version);
+    }
+
+    Ok((parent_link, links))
+}
+
+#[cfg(feature = "blocking")]
+fn read_body(response: Response) -> Result<String, WebError> {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned());
+    let bytes = response.bytes().map_err(WebError::Request)?;
+
+    Ok(decode_body(&bytes, content_type.as_deref()))
+}
+
+/// Decodes the body using the charset declared in the `Content-Type` header,
+/// or by a `<meta>` element in the html page when the header do not declare
+/// any charset. Falls back to `UTF-8` when the charset is unknown.
+pub(crate) fn decode_body(bytes: &[u8], content_type: Option<&str>) -> String {
+    let encoding = content_type
+        .and_then(get_header_charset)
+        .or_else(|| get_meta_charset(bytes))
+        .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+        .unwrap_or(UTF_8);
+
+    let (text, ..) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+fn get_header_charset(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("charset") {
+            Some(value.trim().trim_matches(&['"', '\''][..]).to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+fn get_meta_charset(bytes: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(META_SNIFF_LENGTH)]);
+
+    META_CHARSET_RE
+        .captures(&head)
+        .map(|captures| captures[1].to_owned())
+}
+
+#[cfg(feature = "blocking")]
 fn get_parent_link_element<T: WebResponse>(content: &T) -> LinkElement {
     let headers = content.get_headers();
     let url = content.response().url();
@@ -64,6 +219,10 @@ fn get_parent_link_element<T: WebResponse>(content: &T) -> LinkElement {
         .get(header::CONTENT_TYPE.as_str())
         .unwrap_or(&"UNKNOWN");
 
+    parent_link_element(url, response_type)
+}
+
+pub(crate) fn parent_link_element(url: &Url, response_type: &str) -> LinkElement {
     for (key, val) in MIME_TYPES.iter() {
         if response_type.contains(key) {
             return LinkElement::new(url.clone(), *val);
@@ -73,15 +232,17 @@ fn get_parent_link_element<T: WebResponse>(content: &T) -> LinkElement {
     LinkElement::new(url.clone(), LinkType::Unknown)
 }
 
-fn get_link_elements(
+pub(crate) fn get_link_elements(
     text: String,
     parent_url: Url,
     re: Option<&str>,
+    link_types: &LinkTypeMapping,
+    strip_v_prefix: bool,
 ) -> Result<Vec<LinkElement>, WebError> {
     let document = Document::from(text.as_str());
 
     let re = if let Some(re) = re {
-        Some(Regex::new(&re).map_err(|err| WebError::Other(err.to_string()))?)
+        Some(compile_regex(re)?)
     } else {
         None
     };
@@ -113,7 +274,7 @@ fn get_link_elements(
 
             if let Some(re) = &re {
                 let capture = re.captures(link.link.as_str())?;
-                link.version = parse_version(capture);
+                link.version = parse_version(capture, strip_v_prefix);
             }
 
             link.text = n.text().trim().into();
@@ -129,27 +290,16 @@ fn get_link_elements(
                 }
             }
 
-            let path = link.link.path();
-            if path.ends_with(".html") {
-                link.link_type = LinkType::Html;
-            } else if path.ends_with(".json") {
-                link.link_type = LinkType::Json;
-            } else if path.ends_with(".css") {
-                link.link_type = LinkType::Css;
-            } else if path.ends_with(".txt") {
-                link.link_type = LinkType::Text;
-            } else if path.ends_with(".zip")
-                || path.ends_with(".7z")
-                || path.ends_with(".exe")
-                || path.ends_with(".msi")
-                || path.ends_with(".tar")
-                || path.ends_with(".tar.gz")
-                || path.ends_with(".tar.bz2")
-                || path.ends_with(".nupkg")
-            {
-                link.link_type = LinkType::Binary;
+            // Download buttons usually only contain an icon, in which case the
+            // accessible label is the most descriptive title we can get.
+            if link.text.is_empty() && link.title.is_empty() {
+                if let Some(label) = link.attributes.get("aria-label") {
+                    link.title = label.trim().into();
+                }
             }
 
+            link.link_type = link_types.classify(&link.link);
+
             Some(link)
         })
         .collect();
@@ -157,16 +307,265 @@ fn get_link_elements(
     Ok(results)
 }
 
-fn parse_version(captures: Captures<'_>) -> Option<Versions> {
-    Versions::parse(captures.name("version")?.as_str()).ok()
+pub(crate) fn compile_regex(re: &str) -> Result<Regex, WebError> {
+    Regex::new(re).map_err(|err| WebError::InvalidRegex {
+        pattern: re.into(),
+        error: err.to_string(),
+    })
+}
+
+fn get_page_version(text: &str, re: &Regex, strip_v_prefix: bool) -> Option<Versions> {
+    let document = Document::from(text);
+    let text: String = document.find(Name("body")).map(|n| n.text()).collect();
+
+    parse_version(re.captures(&text)?, strip_v_prefix)
+}
+
+/// Parses the version captured in the group named `version`, optionally
+/// removing a leading `v` or `V` before parsing. This is the same parsing that
+/// is used for the links and page versions of an [HtmlResponse].
+///
+/// Returns `None` if there is no `version` group, or the captured value is not
+/// a valid version.
+pub fn parse_version(captures: Captures<'_>, strip_v_prefix: bool) -> Option<Versions> {
+    let version = captures.name("version")?.as_str();
+    let version = if strip_v_prefix {
+        version.strip_prefix(&['v', 'V'][..]).unwrap_or(version)
+    } else {
+        version
+    };
+
+    Versions::parse(version).ok()
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "blocking"))]
 mod tests {
     use std::collections::HashMap;
 
+    use rstest::rstest;
+
     use super::*;
-    use crate::WebRequest;
+    use crate::{test_server, WebRequest};
+
+    #[test]
+    fn get_link_elements_should_use_title_attribute_on_empty_text() {
+        let html = r#"<a href="/download/test.exe" title="Download installer"><i></i></a>"#;
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let links = get_link_elements(html.into(), parent, None, &LinkTypeMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].title, "Download installer");
+        assert_eq!(links[0].text, "");
+    }
+
+    #[test]
+    fn get_link_elements_should_use_aria_label_on_empty_text_and_title() {
+        let html = r#"<a href="/download/test.exe" aria-label=" Download installer "></a>"#;
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let links = get_link_elements(html.into(), parent, None, &LinkTypeMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].title, "Download installer");
+        assert_eq!(
+            links[0].attributes.get("aria-label"),
+            Some(&" Download installer ".to_owned())
+        );
+    }
+
+    #[test]
+    fn get_link_elements_should_prefer_title_attribute_over_aria_label() {
+        let html = r#"<a href="/test.exe" title="Title" aria-label="Label"></a>"#;
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let links = get_link_elements(html.into(), parent, None, &LinkTypeMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(links[0].title, "Title");
+    }
+
+    #[test]
+    fn get_link_elements_should_not_use_aria_label_when_text_is_available() {
+        let html = r#"<a href="/test.exe" aria-label="Download installer">test.exe</a>"#;
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let links = get_link_elements(html.into(), parent, None, &LinkTypeMapping::default(), true)
+            .unwrap();
+
+        assert_eq!(links[0].title, "");
+        assert_eq!(links[0].text, "test.exe");
+    }
+
+    #[test]
+    fn get_link_elements_should_return_error_on_invalid_regex() {
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let err = get_link_elements(
+            "<a href=\"/test.exe\">test</a>".into(),
+            parent,
+            Some("(?P<version>[\\d"),
+            &LinkTypeMapping::default(),
+            true,
+        )
+        .unwrap_err();
+
+        match err {
+            WebError::InvalidRegex { pattern, .. } => assert_eq!(pattern, "(?P<version>[\\d"),
+            err => panic!("Unexpected error returned: {}", err),
+        }
+    }
+
+    #[test]
+    fn get_page_version_should_extract_version_from_body_text() {
+        let html = "<html><head><title>Version 0.1.0</title></head><body><h1>Latest release: \
+                    <b>v2.4.1</b></h1></body></html>";
+        let re = Regex::new(r"release: v(?P<version>[\d\.]+)").unwrap();
+
+        let version = get_page_version(html, &re, true);
+
+        assert_eq!(version, Some(Versions::parse("2.4.1").unwrap()));
+    }
+
+    #[test]
+    fn get_page_version_should_return_none_when_no_version_is_found() {
+        let html = "<html><body><h1>Latest release</h1></body></html>";
+        let re = Regex::new(r"release: v(?P<version>[\d\.]+)").unwrap();
+
+        let version = get_page_version(html, &re, true);
+
+        assert_eq!(version, None);
+    }
+
+    #[rstest]
+    #[case("/download/v1.2.3/setup.exe", true, Some("1.2.3"))]
+    #[case("/download/V1.2.3/setup.exe", true, Some("1.2.3"))]
+    #[case("/download/1.2.3/setup.exe", true, Some("1.2.3"))]
+    #[case("/download/v1.2.3/setup.exe", false, None)]
+    #[case("/download/1.2.3/setup.exe", false, Some("1.2.3"))]
+    fn get_link_elements_should_strip_v_prefix_from_versions(
+        #[case] href: &str,
+        #[case] strip_v_prefix: bool,
+        #[case] expected: Option<&str>,
+    ) {
+        let html = format!(r#"<a href="{}">Download</a>"#, href);
+        let parent = Url::parse("https://example.org/releases").unwrap();
+
+        let links = get_link_elements(
+            html,
+            parent,
+            Some(r"/download/(?P<version>[vV]?[\d\.]+)/"),
+            &LinkTypeMapping::default(),
+            strip_v_prefix,
+        )
+        .unwrap();
+
+        assert_eq!(
+            links[0].version,
+            expected.map(|v| Versions::parse(v).unwrap())
+        );
+    }
+
+    #[test]
+    fn get_page_version_should_strip_v_prefix() {
+        let html = "<html><body><h1>Latest release: v2.4.1</h1></body></html>";
+        let re = Regex::new(r"release: (?P<version>v[\d\.]+)").unwrap();
+
+        assert_eq!(
+            get_page_version(html, &re, true),
+            Some(Versions::parse("2.4.1").unwrap())
+        );
+        assert_eq!(get_page_version(html, &re, false), None);
+    }
+
+    #[test]
+    fn read_with_version_should_use_page_version_for_links_without_version() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[("Content-Type", "text/html")],
+                b"<html><body><h1>Latest release: v2.4.1</h1>\
+                  <a href=\"/download/setup.exe\">Download</a>\
+                  <a href=\"/download/tool-1.0.0.zip\">Tool</a></body></html>",
+            )
+        });
+        let request = WebRequest::create();
+        let response = request
+            .get_html_response(&format!("{}/releases", url))
+            .unwrap();
+
+        let (parent, links) = response
+            .read_with_version(
+                Some(r"(-(?P<version>[\d\.]+))?\.(exe|zip)$"),
+                r"release: v(?P<version>[\d\.]+)",
+            )
+            .unwrap();
+
+        assert_eq!(parent.version, Some(Versions::parse("2.4.1").unwrap()));
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].version, Some(Versions::parse("2.4.1").unwrap()));
+        assert_eq!(links[1].version, Some(Versions::parse("1.0.0").unwrap()));
+    }
+
+    #[rstest(
+        content_type,
+        case(Some("text/html; charset=windows-1252")),
+        case(Some("text/html; charset=\"Windows-1252\"")),
+        case(None)
+    )]
+    fn decode_body_should_transcode_legacy_encodings(content_type: Option<&str>) {
+        let body: &[u8] = b"<html><head><meta charset=\"windows-1252\"></head>\
+                            <body><a href=\"/caf\xe9.zip\">Caf\xe9 t\xe9l\xe9chargement</a></body></html>";
+
+        let actual = decode_body(body, content_type);
+
+        assert!(actual.contains(">Caf\u{e9} t\u{e9}l\u{e9}chargement<"));
+    }
+
+    #[rstest(
+        content_type,
+        case(Some("text/html")),
+        case(Some("text/html; charset=unknown-charset")),
+        case(None)
+    )]
+    fn decode_body_should_default_to_utf8(content_type: Option<&str>) {
+        let body = "<html><body><a href=\"/\">Caf\u{e9}</a></body></html>";
+
+        let actual = decode_body(body.as_bytes(), content_type);
+
+        assert_eq!(actual, body);
+    }
+
+    #[test]
+    fn get_meta_charset_should_read_http_equiv_content_type() {
+        let body = b"<html><head><meta http-equiv=\"Content-Type\" \
+                     content=\"text/html; charset=ISO-8859-1\"></head></html>";
+
+        let actual = get_meta_charset(body);
+
+        assert_eq!(actual, Some("ISO-8859-1".into()));
+    }
+
+    #[test]
+    fn read_should_decode_windows_1252_link_text() {
+        let url = test_server::serve(|_| {
+            test_server::response(
+                200,
+                &[("Content-Type", "text/html; charset=windows-1252")],
+                b"<html><body><a href=\"/downloads/r\xe9sum\xe9-1.0.0.zip\">\
+                  R\xe9sum\xe9 \x96 Fran\xe7ais</a></body></html>",
+            )
+        });
+        let request = WebRequest::create();
+        let response = request.get_html_response(&format!("{}/", url)).unwrap();
+
+        let (_, links) = response.read(None).unwrap();
+
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].text, "R\u{e9}sum\u{e9} \u{2013} Fran\u{e7}ais");
+    }
 
     #[test]
     fn read_should_get_links_from_page() {
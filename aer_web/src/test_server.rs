@@ -0,0 +1,154 @@
+// Copyright (c) 2021 Kim J. Nordmo and WormieCorp.
+// Licensed under the MIT license. See LICENSE.txt file in the project
+
+//! Minimal local http server only used by the tests, for the cases where the
+//! behavior we want to test can not be reproduced by a remote service (like
+//! interrupted transfers or failing servers).
+//!
+//! The server is available to the tests of other crates in the workspace when
+//! the `test_server` feature is enabled, and is not meant to be used outside
+//! of tests.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+/// The request that was received by the test server.
+pub struct TestRequest {
+    /// The zero based index of the request, the first request received by the
+    /// server will have the index `0`.
+    pub index: usize,
+    /// The http method of the request (*ie: `GET`*).
+    pub method: String,
+    /// The path (and query) that was requested.
+    pub path: String,
+    /// The headers sent by the client, all names are in lowercase.
+    pub headers: HashMap<String, String>,
+}
+
+impl TestRequest {
+    /// Returns the value of the header with the specified lowercase name, or
+    /// `None` if the client did not send the header.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|val| val.as_str())
+    }
+}
+
+/// Starts a new server on a random local port, calling the `handler` for every
+/// request received. The raw bytes returned by the handler will be written as
+/// the response, and the connection closed afterwards.
+///
+/// Returns the base url of the started server.
+pub fn serve<F>(handler: F) -> String
+where
+    F: Fn(&TestRequest) -> Vec<u8> + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+
+            if let Some(request) = read_request(&stream, index) {
+                let _ = stream.write_all(&handler(&request));
+                let _ = stream.flush();
+            }
+        }
+    });
+
+    format!("http://{}", address)
+}
+
+/// Creates a complete response with the specified status, additional headers
+/// and body.
+pub fn response(status: u16, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut response = head(status, headers, body.len());
+    response.extend_from_slice(body);
+
+    response
+}
+
+/// Creates a response that advertises a content length of `length`, but only
+/// includes the specified partial body (simulating an interrupted transfer).
+pub fn truncated_response(body: &[u8], length: usize) -> Vec<u8> {
+    let mut response = head(200, &[], length);
+    response.extend_from_slice(body);
+
+    response
+}
+
+fn head(status: u16, headers: &[(&str, &str)], length: usize) -> Vec<u8> {
+    let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason(status));
+
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+    {
+        head.push_str(&format!("Content-Length: {}\r\n", length));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    head.into_bytes()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        206 => "Partial Content",
+        304 => "Not Modified",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        416 => "Range Not Satisfiable",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn read_request(stream: &TcpStream, index: usize) -> Option<TestRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_owned();
+    let path = parts.next()?.to_owned();
+    let mut headers = HashMap::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            break;
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(index) = header.find(':') {
+            headers.insert(
+                header[..index].trim().to_lowercase(),
+                header[index + 1..].trim().to_owned(),
+            );
+        }
+    }
+
+    Some(TestRequest {
+        index,
+        method,
+        path,
+        headers,
+    })
+}